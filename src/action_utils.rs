@@ -1,3 +1,5 @@
+use crate::results::Location;
+
 pub(crate) fn escape_data<T: AsRef<str>>(s: T) -> String {
     s.as_ref()
         .replace('%', "%25")
@@ -14,4 +16,27 @@ pub(crate) fn escape_property<T: AsRef<str>>(s: T) -> String {
         .replace(',', "%2C")
 }
 
-pub(crate) fn group(group_name: &str) {}
+pub(crate) fn group(group_name: &str) {
+    println!("::group::{}", escape_data(group_name));
+}
+
+pub(crate) fn end_group() {
+    println!("::endgroup::");
+}
+
+/// Emits an `error`, `warning` or `notice` workflow command, attached to a file when one is known.
+pub(crate) fn annotate(command: &str, title: &str, location: Option<&Location>, message: &str) {
+    let mut properties = vec![format!("title={}", escape_property(title))];
+    if let Some(l) = location {
+        properties.push(format!("file={}", escape_property(&l.path)));
+        if let Some(line) = l.line {
+            properties.push(format!("line={}", line));
+        }
+    }
+    println!(
+        "::{} {}::{}",
+        command,
+        properties.join(","),
+        escape_data(message)
+    );
+}
@@ -0,0 +1,172 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::{
+    checks::{Check, CheckCategory},
+    github_utils::{octocrab_with_access_token_for, request_failure_reason, RateThrottle},
+    inputs::Inputs,
+    results::{CheckResult, Severity},
+};
+
+pub(crate) struct WorkflowPermissions;
+
+#[async_trait]
+impl Check for WorkflowPermissions {
+    fn id(&self) -> &'static str {
+        "workflow_permissions"
+    }
+
+    fn title(&self) -> &'static str {
+        "Default Workflow Permissions"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Workflows
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Under the repository's and organization's Actions settings, set workflow permissions to read-only, stop Actions approving pull requests, and only allow local or selected actions."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn reads_commit(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_workflow_permissions(requests, inputs).await
+    }
+}
+
+#[derive(Deserialize)]
+struct DefaultWorkflowPermissions {
+    default_workflow_permissions: String,
+    can_approve_pull_request_reviews: bool,
+}
+
+#[derive(Deserialize)]
+struct ActionsPermissions {
+    allowed_actions: Option<String>,
+}
+
+static DEFAULT_ALLOWED_ACTIONS: [&str; 2] = ["local_only", "selected"];
+
+fn token_findings(scope: &str, permissions: &DefaultWorkflowPermissions) -> Vec<CheckResult> {
+    let mut findings = Vec::new();
+    if permissions.default_workflow_permissions == "read" {
+        findings.push(CheckResult::Pass(format!(
+            "The {} default workflow token is read-only",
+            scope
+        )));
+    } else {
+        findings.push(CheckResult::Failure(format!(
+            "The {} default workflow token has `{}` permissions instead of `read`.",
+            scope, permissions.default_workflow_permissions
+        )));
+    }
+    if permissions.can_approve_pull_request_reviews {
+        findings.push(CheckResult::Failure(format!(
+            "The {} settings allow GitHub Actions to approve pull requests.",
+            scope
+        )));
+    }
+    findings
+}
+
+fn allowed_actions_finding(
+    scope: &str,
+    permissions: &ActionsPermissions,
+    allowed: &[String],
+) -> CheckResult {
+    // Actions being disabled altogether leaves `allowed_actions` unset.
+    match permissions.allowed_actions.as_deref() {
+        None => CheckResult::Pass(format!("GitHub Actions are disabled for the {}", scope)),
+        Some(a) if allowed.iter().any(|x| x == a) => {
+            CheckResult::Pass(format!("The {} allows `{}` actions", scope, a))
+        }
+        Some(a) => CheckResult::Failure(format!(
+            "The {} allows `{}` actions; expected one of `{}`.",
+            scope,
+            a,
+            allowed.join("`, `")
+        )),
+    }
+}
+
+async fn verify_workflow_permissions(
+    mut requests: RateThrottle,
+    inputs: Inputs,
+) -> Vec<CheckResult> {
+    let allowed_actions: Vec<String> = inputs
+        .config
+        .option("workflow_permissions", "allowed_actions")
+        .unwrap_or_else(|| DEFAULT_ALLOWED_ACTIONS.map(|a| a.to_owned()).to_vec());
+    let oc = octocrab_with_access_token_for(&inputs);
+    let mut results = Vec::new();
+
+    let sem = requests.acquire().await;
+    let repo_token: Result<DefaultWorkflowPermissions, _> = oc
+        .get(
+            format!("/repos/{}/actions/permissions/workflow", inputs.repository),
+            None::<&()>,
+        )
+        .await;
+    let repo_actions: Result<ActionsPermissions, _> = oc
+        .get(
+            format!("/repos/{}/actions/permissions", inputs.repository),
+            None::<&()>,
+        )
+        .await;
+    drop(sem);
+    match repo_token {
+        Ok(p) => results.extend(token_findings("repository", &p)),
+        Err(e) => results.push(CheckResult::Failure(format!(
+            "Could not read the repository's default workflow permissions: {}",
+            request_failure_reason(&e)
+        ))),
+    }
+    match repo_actions {
+        Ok(p) => results.push(allowed_actions_finding("repository", &p, &allowed_actions)),
+        Err(e) => results.push(CheckResult::Failure(format!(
+            "Could not read the repository's allowed actions: {}",
+            request_failure_reason(&e)
+        ))),
+    }
+
+    // Organization settings are the defaults new repositories start with.  They can't be read
+    // for repositories owned by a user, or without organization administration access, so they
+    // are only reported when available.
+    let _permit = requests.acquire().await;
+    let org_token: Result<DefaultWorkflowPermissions, _> = oc
+        .get(
+            format!(
+                "/orgs/{}/actions/permissions/workflow",
+                inputs.repository_owner
+            ),
+            None::<&()>,
+        )
+        .await;
+    if let Ok(p) = org_token {
+        results.extend(
+            token_findings("organization", &p)
+                .into_iter()
+                .map(|r| r.with_severity(Severity::Warning)),
+        );
+    }
+    let org_actions: Result<ActionsPermissions, _> = oc
+        .get(
+            format!("/orgs/{}/actions/permissions", inputs.repository_owner),
+            None::<&()>,
+        )
+        .await;
+    if let Ok(p) = org_actions {
+        results.push(
+            allowed_actions_finding("organization", &p, &allowed_actions)
+                .with_severity(Severity::Warning),
+        );
+    }
+    results
+}
@@ -1,26 +1,718 @@
+use std::fmt::Display;
+
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use serde::Deserialize;
+
 use crate::{
-    github_utils::{octocrab_repo_handler_for, octocrab_with_token_for, RateThrottle},
+    checks::{Check, CheckCategory},
+    github_utils::{
+        octocrab_repo_handler_for, octocrab_with_access_token_for, octocrab_with_token_for,
+        request_failure_reason, RateThrottle,
+    },
     inputs::Inputs,
-    results::CheckResult,
+    results::{CheckResult, Location},
+    workflow_utils::{grab_repo_workflows, Workflow},
 };
 
-pub(crate) async fn verify_default_branch_protected(
+pub(crate) struct DefaultBranchProtected;
+
+#[async_trait]
+impl Check for DefaultBranchProtected {
+    fn id(&self) -> &'static str {
+        "default_branch_protected"
+    }
+
+    fn title(&self) -> &'static str {
+        "Default Branch Protection"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::BranchProtection
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add a branch protection rule for the default branch which meets the configured requirements."
+    }
+
+    fn reads_commit(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_default_branch_protected(requests, inputs).await
+    }
+}
+
+pub(crate) struct RequiredStatusChecksExist;
+
+#[async_trait]
+impl Check for RequiredStatusChecksExist {
+    fn id(&self) -> &'static str {
+        "required_status_checks"
+    }
+
+    fn title(&self) -> &'static str {
+        "Required Status Checks Match Workflows"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::BranchProtection
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Remove required status checks which no workflow reports, and require the jobs of important workflows."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_required_status_checks(requests, inputs).await
+    }
+}
+
+/// Requirements the default branch's protection must meet, read from the check's options.
+struct ProtectionPolicy {
+    min_approving_reviews: Option<u64>,
+    dismiss_stale_reviews: bool,
+    require_code_owner_reviews: bool,
+    required_status_checks: Vec<String>,
+    disallow_force_pushes: bool,
+    disallow_deletions: bool,
+    enforce_admins: bool,
+    require_linear_history: bool,
+}
+
+impl ProtectionPolicy {
+    fn from_inputs(inputs: &Inputs) -> Self {
+        let option = |key: &str| {
+            inputs
+                .config
+                .option::<bool>("default_branch_protected", key)
+                .unwrap_or(false)
+        };
+        ProtectionPolicy {
+            min_approving_reviews: inputs
+                .config
+                .option("default_branch_protected", "min_approving_reviews"),
+            dismiss_stale_reviews: option("dismiss_stale_reviews"),
+            require_code_owner_reviews: option("require_code_owner_reviews"),
+            required_status_checks: inputs
+                .config
+                .option("default_branch_protected", "required_status_checks")
+                .unwrap_or_default(),
+            disallow_force_pushes: option("disallow_force_pushes"),
+            disallow_deletions: option("disallow_deletions"),
+            enforce_admins: option("enforce_admins"),
+            require_linear_history: option("require_linear_history"),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min_approving_reviews.is_none()
+            && !self.dismiss_stale_reviews
+            && !self.require_code_owner_reviews
+            && self.required_status_checks.is_empty()
+            && !self.disallow_force_pushes
+            && !self.disallow_deletions
+            && !self.enforce_admins
+            && !self.require_linear_history
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct BranchProtection {
+    required_status_checks: Option<RequiredStatusChecks>,
+    required_pull_request_reviews: Option<RequiredReviews>,
+    enforce_admins: Option<Toggle>,
+    required_linear_history: Option<Toggle>,
+    allow_force_pushes: Option<Toggle>,
+    allow_deletions: Option<Toggle>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RequiredStatusChecks {
+    contexts: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RequiredReviews {
+    dismiss_stale_reviews: bool,
+    require_code_owner_reviews: bool,
+    required_approving_review_count: u64,
+}
+
+#[derive(Deserialize)]
+struct Toggle {
+    enabled: bool,
+}
+
+fn toggled(toggle: &Option<Toggle>) -> bool {
+    toggle.as_ref().is_some_and(|t| t.enabled)
+}
+
+/// The part of a branch which says whether branch protection covers it, readable without
+/// administration access.
+#[derive(Deserialize)]
+struct BranchSummary {
+    protected: bool,
+}
+
+/// A rule from a repository or organization ruleset which applies to a branch.
+#[derive(Deserialize)]
+struct BranchRule {
+    #[serde(rename = "type")]
+    rule_type: String,
+    #[serde(default)]
+    ruleset_source_type: String,
+    #[serde(default)]
+    ruleset_source: String,
+    ruleset_id: Option<u64>,
+    #[serde(default)]
+    parameters: serde_json::Value,
+}
+
+/// Where a requirement on a branch is enforced from.
+#[derive(Clone, PartialEq)]
+enum RuleSource {
+    BranchProtection,
+    Ruleset(String),
+}
+
+impl Display for RuleSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleSource::BranchProtection => f.write_str("branch protection"),
+            RuleSource::Ruleset(r) => f.write_str(r),
+        }
+    }
+}
+
+impl RuleSource {
+    fn for_rule(rule: &BranchRule) -> Self {
+        let kind = if rule.ruleset_source_type == "Organization" {
+            "organization ruleset"
+        } else {
+            "repository ruleset"
+        };
+        match rule.ruleset_id {
+            Some(id) => RuleSource::Ruleset(format!("{} #{} ({})", kind, id, rule.ruleset_source)),
+            None => RuleSource::Ruleset(format!("{} ({})", kind, rule.ruleset_source)),
+        }
+    }
+}
+
+/// The requirements on a branch from classic branch protection and rulesets combined, along
+/// with the sources enforcing each of them.
+#[derive(Default)]
+struct EffectiveProtection {
+    protected: bool,
+    approving_reviews: Vec<(u64, RuleSource)>,
+    dismiss_stale_reviews: Vec<RuleSource>,
+    code_owner_reviews: Vec<RuleSource>,
+    status_checks: Vec<(String, RuleSource)>,
+    no_force_pushes: Vec<RuleSource>,
+    no_deletions: Vec<RuleSource>,
+    enforce_admins: Vec<RuleSource>,
+    linear_history: Vec<RuleSource>,
+}
+
+impl EffectiveProtection {
+    fn merge(classic: Option<&BranchProtection>, rules: &[BranchRule]) -> Self {
+        let mut effective = EffectiveProtection::default();
+        if let Some(p) = classic {
+            let source = RuleSource::BranchProtection;
+            if let Some(r) = p.required_pull_request_reviews.as_ref() {
+                effective
+                    .approving_reviews
+                    .push((r.required_approving_review_count, source.clone()));
+                if r.dismiss_stale_reviews {
+                    effective.dismiss_stale_reviews.push(source.clone());
+                }
+                if r.require_code_owner_reviews {
+                    effective.code_owner_reviews.push(source.clone());
+                }
+            }
+            if let Some(c) = p.required_status_checks.as_ref() {
+                effective
+                    .status_checks
+                    .extend(c.contexts.iter().map(|c| (c.clone(), source.clone())));
+            }
+            if !toggled(&p.allow_force_pushes) {
+                effective.no_force_pushes.push(source.clone());
+            }
+            if !toggled(&p.allow_deletions) {
+                effective.no_deletions.push(source.clone());
+            }
+            if toggled(&p.enforce_admins) {
+                effective.enforce_admins.push(source.clone());
+            }
+            if toggled(&p.required_linear_history) {
+                effective.linear_history.push(source);
+            }
+        }
+        for rule in rules {
+            let source = RuleSource::for_rule(rule);
+            let parameters = &rule.parameters;
+            match rule.rule_type.as_str() {
+                "pull_request" => {
+                    let count = parameters
+                        .get("required_approving_review_count")
+                        .and_then(|c| c.as_u64())
+                        .unwrap_or(0);
+                    effective.approving_reviews.push((count, source.clone()));
+                    let flag = |key: &str| parameters.get(key).and_then(|v| v.as_bool());
+                    if flag("dismiss_stale_reviews_on_push") == Some(true) {
+                        effective.dismiss_stale_reviews.push(source.clone());
+                    }
+                    if flag("require_code_owner_review") == Some(true) {
+                        effective.code_owner_reviews.push(source);
+                    }
+                }
+                "required_status_checks" => effective.status_checks.extend(
+                    parameters
+                        .get("required_status_checks")
+                        .and_then(|c| c.as_array())
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|c| c.get("context")?.as_str())
+                        .map(|c| (c.to_owned(), source.clone())),
+                ),
+                "non_fast_forward" => effective.no_force_pushes.push(source),
+                "deletion" => effective.no_deletions.push(source),
+                "required_linear_history" => effective.linear_history.push(source),
+                _ => {}
+            }
+        }
+        effective
+    }
+
+    /// Every source which enforces at least one requirement.
+    fn sources(&self) -> Vec<RuleSource> {
+        let mut sources: Vec<RuleSource> = Vec::new();
+        let all = self
+            .approving_reviews
+            .iter()
+            .map(|(_, s)| s)
+            .chain(self.dismiss_stale_reviews.iter())
+            .chain(self.code_owner_reviews.iter())
+            .chain(self.status_checks.iter().map(|(_, s)| s))
+            .chain(self.no_force_pushes.iter())
+            .chain(self.no_deletions.iter())
+            .chain(self.enforce_admins.iter())
+            .chain(self.linear_history.iter());
+        for source in all {
+            if !sources.contains(source) {
+                sources.push(source.clone());
+            }
+        }
+        sources
+    }
+}
+
+fn enforced_by(sources: &[&RuleSource]) -> String {
+    if sources.is_empty() {
+        return String::new();
+    }
+    let names: Vec<String> = sources.iter().map(|s| s.to_string()).collect();
+    format!(" (enforced by {})", names.join(", "))
+}
+
+fn requirement(sources: &[RuleSource], pass: &str, failure: &str) -> CheckResult {
+    if sources.is_empty() {
+        CheckResult::Failure(failure.to_owned())
+    } else {
+        let sources: Vec<&RuleSource> = sources.iter().collect();
+        CheckResult::Pass(format!("{}{}", pass, enforced_by(&sources)))
+    }
+}
+
+fn evaluate_protection(
+    policy: &ProtectionPolicy,
+    protection: &EffectiveProtection,
+) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+    if let Some(min) = policy.min_approving_reviews {
+        let count = protection
+            .approving_reviews
+            .iter()
+            .map(|(c, _)| *c)
+            .max()
+            .unwrap_or(0);
+        if count >= min {
+            let sources: Vec<&RuleSource> = protection
+                .approving_reviews
+                .iter()
+                .filter(|(c, _)| *c >= min)
+                .map(|(_, s)| s)
+                .collect();
+            results.push(CheckResult::Pass(format!(
+                "Default Branch requires {} approving reviews{}",
+                count,
+                enforced_by(&sources)
+            )));
+        } else {
+            results.push(CheckResult::Failure(format!(
+                "Default Branch requires {} approving reviews, fewer than {}.",
+                count, min
+            )));
+        }
+    }
+    if policy.dismiss_stale_reviews {
+        results.push(requirement(
+            &protection.dismiss_stale_reviews,
+            "Default Branch dismisses stale reviews",
+            "Default Branch does not dismiss stale reviews.",
+        ));
+    }
+    if policy.require_code_owner_reviews {
+        results.push(requirement(
+            &protection.code_owner_reviews,
+            "Default Branch requires code owner review",
+            "Default Branch does not require code owner review.",
+        ));
+    }
+    for context in policy.required_status_checks.iter() {
+        let sources: Vec<RuleSource> = protection
+            .status_checks
+            .iter()
+            .filter(|(c, _)| c == context)
+            .map(|(_, s)| s.clone())
+            .collect();
+        results.push(requirement(
+            &sources,
+            &format!("Default Branch requires the `{}` status check", context),
+            &format!(
+                "Default Branch does not require the `{}` status check.",
+                context
+            ),
+        ));
+    }
+    if policy.disallow_force_pushes {
+        results.push(requirement(
+            &protection.no_force_pushes,
+            "Default Branch blocks force pushes",
+            "Default Branch allows force pushes.",
+        ));
+    }
+    if policy.disallow_deletions {
+        results.push(requirement(
+            &protection.no_deletions,
+            "Default Branch cannot be deleted",
+            "Default Branch allows deletion.",
+        ));
+    }
+    if policy.enforce_admins {
+        results.push(requirement(
+            &protection.enforce_admins,
+            "Default Branch protection applies to administrators",
+            "Default Branch protection does not apply to administrators.",
+        ));
+    }
+    if policy.require_linear_history {
+        results.push(requirement(
+            &protection.linear_history,
+            "Default Branch requires linear history",
+            "Default Branch does not require linear history.",
+        ));
+    }
+    results
+}
+
+async fn branch_summary(
+    oc: &Octocrab,
+    inputs: &Inputs,
+    branch: &str,
+) -> Result<BranchSummary, octocrab::Error> {
+    oc.get(
+        format!("/repos/{}/branches/{}", inputs.repository, branch),
+        None::<&()>,
+    )
+    .await
+}
+
+async fn branch_rules(
+    oc: &Octocrab,
+    inputs: &Inputs,
+    branch: &str,
+) -> Result<Vec<BranchRule>, octocrab::Error> {
+    oc.get(
+        format!(
+            "/repos/{}/rules/branches/{}?per_page=100",
+            inputs.repository, branch
+        ),
+        None::<&()>,
+    )
+    .await
+}
+
+/// Fetches classic branch protection, which is only visible to administrators, and the
+/// ruleset rules which apply to a branch, and merges them.
+async fn effective_protection(
+    requests: &mut RateThrottle,
+    inputs: &Inputs,
+    branch: &str,
+) -> Result<EffectiveProtection, String> {
+    let oc = octocrab_with_token_for(inputs);
+    let access_oc = octocrab_with_access_token_for(inputs);
+    let _permit = requests.acquire().await;
+    let classic: Option<BranchProtection> = match access_oc
+        .get(
+            format!(
+                "/repos/{}/branches/{}/protection",
+                inputs.repository, branch
+            ),
+            None::<&()>,
+        )
+        .await
+    {
+        Ok(p) => Some(p),
+        Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => None,
+        Err(e) => {
+            return Err(format!(
+                "Could not check the protection of `{}`: {}",
+                branch,
+                request_failure_reason(&e)
+            ))
+        }
+    };
+    let rules = branch_rules(&oc, inputs, branch).await.map_err(|e| {
+        format!(
+            "Could not check the rulesets of `{}`: {}",
+            branch,
+            request_failure_reason(&e)
+        )
+    })?;
+    let mut effective = EffectiveProtection::merge(classic.as_ref(), &rules);
+    effective.protected = classic.is_some() || !rules.is_empty();
+    Ok(effective)
+}
+
+async fn default_branch_name(
+    requests: &mut RateThrottle,
+    inputs: &Inputs,
+) -> Result<String, String> {
+    let oc = octocrab_with_token_for(inputs);
+    let rh = octocrab_repo_handler_for(&oc, inputs);
+    let _permit = requests.acquire().await;
+    match rh.get().await {
+        Ok(r) => Ok(r.default_branch.unwrap_or_else(|| "main".to_owned())),
+        Err(e) => Err(format!(
+            "Could not find the default branch: {}",
+            request_failure_reason(&e)
+        )),
+    }
+}
+
+async fn verify_default_branch_protected(
     mut results: RateThrottle,
     inputs: Inputs,
 ) -> Vec<CheckResult> {
     let oc = octocrab_with_token_for(&inputs);
-    let rh = octocrab_repo_handler_for(&oc, &inputs);
-    let _ = results.acquire().await;
-    let default_name = rh.get().await.unwrap().default_branch.unwrap();
-    let protected_list_builder = rh.list_branches().protected(true);
-    let list_result = protected_list_builder.send();
-    for bl in list_result.await.unwrap().into_iter() {
-        if bl.name == default_name {}
-        {
-            return vec![CheckResult::Pass("Default Branch is Protected".to_owned())];
-        }
-    }
-    vec![CheckResult::Failure(
-        "Default Branch is not Protected".to_owned(),
-    )]
+    let policy = ProtectionPolicy::from_inputs(&inputs);
+    let default_name = match default_branch_name(&mut results, &inputs).await {
+        Ok(b) => b,
+        Err(e) => return vec![CheckResult::Failure(e)],
+    };
+    if policy.is_empty() {
+        // Without a policy, avoid needing administration access by only asking whether the
+        // branch is protected, either classically or by a ruleset.
+        let sem = results.acquire().await;
+        let rules = match branch_rules(&oc, &inputs, &default_name).await {
+            Ok(r) => r,
+            Err(e) => {
+                return vec![CheckResult::Failure(format!(
+                    "Could not check the rulesets of the default branch: {}",
+                    request_failure_reason(&e)
+                ))]
+            }
+        };
+        let mut sources: Vec<RuleSource> = Vec::new();
+        for source in rules.iter().map(RuleSource::for_rule) {
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+        let branch = match branch_summary(&oc, &inputs, &default_name).await {
+            Ok(b) => b,
+            Err(e) => {
+                return vec![CheckResult::Failure(format!(
+                    "Could not check whether the default branch is protected: {}",
+                    request_failure_reason(&e)
+                ))]
+            }
+        };
+        drop(sem);
+        if branch.protected {
+            sources.insert(0, RuleSource::BranchProtection);
+        }
+        if sources.is_empty() {
+            return vec![CheckResult::Failure(
+                "Default Branch is not Protected".to_owned(),
+            )];
+        }
+        return vec![CheckResult::Pass(format!(
+            "Default Branch is Protected{}",
+            enforced_by(&sources.iter().collect::<Vec<_>>())
+        ))];
+    }
+
+    let protection = match effective_protection(&mut results, &inputs, &default_name).await {
+        Ok(p) => p,
+        Err(e) => return vec![CheckResult::Failure(e)],
+    };
+    if !protection.protected {
+        return vec![CheckResult::Failure(
+            "Default Branch is not Protected".to_owned(),
+        )];
+    }
+    let mut checked = vec![CheckResult::Pass(format!(
+        "Default Branch is Protected{}",
+        enforced_by(&protection.sources().iter().collect::<Vec<_>>())
+    ))];
+    checked.extend(evaluate_protection(&policy, &protection));
+    checked
+}
+
+/// Whether a workflow job can report a status check with the given context.
+///
+/// Matrix jobs report `name (values)`, jobs calling a reusable workflow report `name / job`, and
+/// names built from expressions are matched on the text around the expressions.
+fn job_produces(job_id: &str, job: &serde_yaml::Value, context: &str) -> bool {
+    let name = job_name(job_id, job);
+    if let Some((prefix, suffix)) = literal_ends(name) {
+        return !(prefix.is_empty() && suffix.is_empty())
+            && context.len() >= prefix.len() + suffix.len()
+            && context.starts_with(prefix)
+            && context.ends_with(suffix);
+    }
+    context == name
+        || (job.get("strategy").and_then(|s| s.get("matrix")).is_some()
+            && context.starts_with(&format!("{} (", name)))
+        || (job.get("uses").is_some() && context.starts_with(&format!("{} / ", name)))
+}
+
+fn job_name<'a>(job_id: &'a str, job: &'a serde_yaml::Value) -> &'a str {
+    job.get("name").and_then(|n| n.as_str()).unwrap_or(job_id)
+}
+
+/// The text before the first expression and after the last one, if the name has any.
+fn literal_ends(name: &str) -> Option<(&str, &str)> {
+    let (prefix, _) = name.split_once("${{")?;
+    let (_, suffix) = name.rsplit_once("}}")?;
+    Some((prefix.trim_end(), suffix.trim_start()))
+}
+
+fn workflow_produces(workflow: &Workflow, context: &str) -> bool {
+    workflow
+        .jobs()
+        .into_iter()
+        .any(|(id, job)| job_produces(id, job, context))
+}
+
+/// The first job whose name is nothing but expressions, so it could report any status check.
+fn unmatchable_job(workflows: &[Workflow]) -> Option<(&Workflow, &str)> {
+    workflows.iter().find_map(|w| {
+        w.jobs()
+            .into_iter()
+            .map(|(id, job)| job_name(id, job))
+            .find(|name| literal_ends(name) == Some(("", "")))
+            .map(|name| (w, name))
+    })
+}
+
+fn workflow_file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+async fn verify_required_status_checks(
+    mut requests: RateThrottle,
+    inputs: Inputs,
+) -> Vec<CheckResult> {
+    let ignore_contexts: Vec<String> = inputs
+        .config
+        .option("required_status_checks", "ignore_contexts")
+        .unwrap_or_default();
+    let important_workflows: Vec<String> = inputs
+        .config
+        .option("required_status_checks", "important_workflows")
+        .unwrap_or_default();
+    let default_name = match default_branch_name(&mut requests, &inputs).await {
+        Ok(b) => b,
+        Err(e) => return vec![CheckResult::Failure(e)],
+    };
+    let protection = match effective_protection(&mut requests, &inputs, &default_name).await {
+        Ok(p) => p,
+        Err(e) => return vec![CheckResult::Failure(e)],
+    };
+
+    let oc = octocrab_with_token_for(&inputs);
+    let workflows = match grab_repo_workflows(&mut requests, &oc, &inputs).await {
+        Ok(w) => w,
+        Err(reason) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not read workflows to check required status checks: {}",
+                reason
+            ))]
+        }
+    };
+
+    let mut results = Vec::new();
+    let mut contexts: Vec<&(String, RuleSource)> = Vec::new();
+    for required in protection.status_checks.iter() {
+        if !contexts.iter().any(|(c, _)| *c == required.0) {
+            contexts.push(required);
+        }
+    }
+    for (context, source) in contexts.iter() {
+        if ignore_contexts.contains(context) {
+            continue;
+        }
+        match workflows.iter().find(|w| workflow_produces(w, context)) {
+            Some(w) => results.push(CheckResult::Pass(format!(
+                "Required status check `{}` is reported by `{}`",
+                context, w.path
+            ))),
+            None => match unmatchable_job(&workflows) {
+                Some((w, name)) => results.push(CheckResult::Notice(format!(
+                    "Required status check `{}` (from {}) was not matched to a workflow, but `{}` in `{}` is named by an expression and may report it.",
+                    context, source, name, w.path
+                ))),
+                None => results.push(CheckResult::Failure(format!(
+                    "Required status check `{}` (from {}) is not reported by any workflow, so pull requests can never satisfy it.",
+                    context, source
+                ))),
+            },
+        }
+    }
+
+    for workflow in workflows.iter().filter(|w| {
+        important_workflows
+            .iter()
+            .any(|i| i == workflow_file_name(&w.path) || *i == w.path)
+    }) {
+        let required = contexts.iter().any(|(c, _)| workflow_produces(workflow, c));
+        if !required {
+            results.push(
+                CheckResult::Warning(format!(
+                    "No job of `{}` is a required status check on `{}`.",
+                    workflow.path, default_name
+                ))
+                .at(Location::file(workflow.path.clone())),
+            );
+        }
+    }
+
+    if results.is_empty() {
+        results.push(CheckResult::Pass(format!(
+            "`{}` has no required status checks to compare with workflows",
+            default_name
+        )));
+    }
+    results
 }
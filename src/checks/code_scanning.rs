@@ -0,0 +1,313 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_yaml::Value;
+
+use crate::{
+    checks::{Check, CheckCategory},
+    github_utils::{
+        list_tree, octocrab_repo_handler_for, octocrab_with_access_token_for,
+        octocrab_with_token_for, request_failure_reason, RateThrottle,
+    },
+    inputs::Inputs,
+    results::{CheckResult, Location},
+    workflow_utils::{grab_repo_workflows, uses_action, Workflow},
+};
+
+pub(crate) struct CodeScanning;
+
+#[async_trait]
+impl Check for CodeScanning {
+    fn id(&self) -> &'static str {
+        "code_scanning"
+    }
+
+    fn title(&self) -> &'static str {
+        "Code Scanning Configured"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::CodeScanning
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Enable CodeQL default setup under the repository's Code security settings, or add a workflow using `github/codeql-action/analyze` which covers every language in the repository."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_code_scanning(requests, inputs).await
+    }
+}
+
+#[derive(Deserialize)]
+struct DefaultSetup {
+    state: String,
+    #[serde(default)]
+    languages: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Analysis {
+    created_at: DateTime<Utc>,
+}
+
+// File extensions of the languages CodeQL can analyse, by CodeQL language.
+static LANGUAGE_EXTENSIONS: [(&str, &[&str]); 8] = [
+    ("c-cpp", &["c", "cc", "cpp", "cxx", "h", "hpp"]),
+    ("csharp", &["cs"]),
+    ("go", &["go"]),
+    ("java-kotlin", &["java", "kt"]),
+    (
+        "javascript-typescript",
+        &["js", "jsx", "mjs", "cjs", "ts", "tsx"],
+    ),
+    ("python", &["py"]),
+    ("ruby", &["rb"]),
+    ("swift", &["swift"]),
+];
+
+/// Folds the older single language names into the combined names CodeQL now uses.
+fn codeql_language(name: &str) -> String {
+    match name.trim().to_lowercase().as_str() {
+        "javascript" | "typescript" => "javascript-typescript".to_owned(),
+        "java" | "kotlin" => "java-kotlin".to_owned(),
+        "c" | "cpp" => "c-cpp".to_owned(),
+        other => other.to_owned(),
+    }
+}
+
+fn detect_languages(paths: &[String], ignore_languages: &[String]) -> BTreeSet<&'static str> {
+    paths
+        .iter()
+        .filter(|p| {
+            !p.split('/')
+                .any(|segment| segment == "node_modules" || segment == "vendor")
+        })
+        .filter_map(|p| {
+            let (_, extension) = p.rsplit_once('.')?;
+            LANGUAGE_EXTENSIONS
+                .iter()
+                .find(|(_, extensions)| extensions.contains(&extension))
+                .map(|(language, _)| *language)
+        })
+        .filter(|l| !ignore_languages.iter().any(|i| codeql_language(i) == *l))
+        .collect()
+}
+
+/// The languages given to `github/codeql-action/init`, expanding a `${{ matrix.* }}` reference
+/// from the job's matrix.  Returns `None` if the languages can't be worked out.
+fn init_languages(job: &Value, step: &Value) -> Option<Vec<String>> {
+    let languages = step.get("with")?.get("languages")?.as_str()?;
+    let matrix_key = languages
+        .trim()
+        .strip_prefix("${{")
+        .and_then(|l| l.strip_suffix("}}"))
+        .map(|l| l.trim())
+        .and_then(|l| l.strip_prefix("matrix."));
+    match matrix_key {
+        Some(key) => {
+            let matrix = job.get("strategy")?.get("matrix")?;
+            let mut values: Vec<String> = matrix
+                .get(key)
+                .and_then(|v| v.as_sequence())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().map(|s| s.to_owned()))
+                .collect();
+            values.extend(
+                matrix
+                    .get("include")
+                    .and_then(|i| i.as_sequence())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|i| i.get(key)?.as_str().map(|s| s.to_owned())),
+            );
+            (!values.is_empty()).then_some(values)
+        }
+        None if languages.contains("${{") => None,
+        None => Some(languages.split(',').map(|l| l.to_owned()).collect()),
+    }
+}
+
+/// The languages analysed by a workflow which runs CodeQL, or `None` if it doesn't run CodeQL.
+/// The inner `None` means CodeQL runs but its languages couldn't be worked out.
+fn workflow_codeql_languages(workflow: &Workflow) -> Option<Option<BTreeSet<String>>> {
+    let mut analyzes = false;
+    let mut languages = Some(BTreeSet::new());
+    for (_, job) in workflow.jobs() {
+        let steps = job.get("steps").and_then(|s| s.as_sequence());
+        for step in steps.into_iter().flatten() {
+            if uses_action(step, "github/codeql-action/analyze") {
+                analyzes = true;
+            }
+            if uses_action(step, "github/codeql-action/init") {
+                match (init_languages(job, step), languages.as_mut()) {
+                    (Some(l), Some(all)) => all.extend(l.iter().map(|l| codeql_language(l))),
+                    _ => languages = None,
+                }
+            }
+        }
+    }
+    analyzes.then_some(languages)
+}
+
+async fn verify_code_scanning(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let access_oc = octocrab_with_access_token_for(&inputs);
+    let oc = octocrab_with_token_for(&inputs);
+    let mut results = Vec::new();
+
+    let sem = requests.acquire().await;
+    let default_setup: Option<DefaultSetup> = match access_oc
+        .get::<DefaultSetup, _, _>(
+            format!("/repos/{}/code-scanning/default-setup", inputs.repository),
+            None::<&()>,
+        )
+        .await
+    {
+        Ok(d) => Some(d).filter(|d| d.state == "configured"),
+        Err(e) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not check if code scanning default setup was configured: {}",
+                request_failure_reason(&e)
+            ))]
+        }
+    };
+    // Only language coverage needs every path, so a failed listing just skips that part.
+    let paths = list_tree(&oc, &inputs).await;
+    drop(sem);
+
+    let configured_languages = match default_setup {
+        Some(setup) => {
+            results.push(CheckResult::Pass(
+                "Code scanning default setup is configured".to_owned(),
+            ));
+            Some(setup.languages.iter().map(|l| codeql_language(l)).collect())
+        }
+        None => {
+            let workflows = match grab_repo_workflows(&mut requests, &oc, &inputs).await {
+                Ok(w) => w,
+                Err(reason) => {
+                    return vec![CheckResult::Failure(format!(
+                        "Could not read workflows to check code scanning: {}",
+                        reason
+                    ))]
+                }
+            };
+            let codeql_workflows: Vec<(&Workflow, Option<BTreeSet<String>>)> = workflows
+                .iter()
+                .filter_map(|w| workflow_codeql_languages(w).map(|l| (w, l)))
+                .collect();
+            if codeql_workflows.is_empty() {
+                return vec![CheckResult::Failure(
+                    "Code scanning is not configured: default setup is off and no workflow uses `github/codeql-action/analyze`.".to_owned(),
+                )];
+            }
+            for (workflow, _) in codeql_workflows.iter() {
+                results.push(
+                    CheckResult::Pass(format!(
+                        "Code scanning is configured in `{}`",
+                        workflow.path
+                    ))
+                    .at(Location::file(workflow.path.clone())),
+                );
+            }
+            codeql_workflows
+                .into_iter()
+                .try_fold(BTreeSet::new(), |mut all, (_, l)| {
+                    all.extend(l?);
+                    Some(all)
+                })
+        }
+    };
+
+    let max_age_days: i64 = inputs
+        .config
+        .option("code_scanning", "max_analysis_age_days")
+        .unwrap_or(7);
+    let rh = octocrab_repo_handler_for(&oc, &inputs);
+    let sem = requests.acquire().await;
+    let default_branch = match rh.get().await {
+        Ok(r) => r.default_branch.unwrap_or_else(|| "main".to_owned()),
+        Err(e) => {
+            results.push(CheckResult::Failure(format!(
+                "Could not find the default branch to check code scanning analyses: {}",
+                request_failure_reason(&e)
+            )));
+            return results;
+        }
+    };
+    let analyses: Result<Vec<Analysis>, _> = access_oc
+        .get(
+            format!(
+                "/repos/{}/code-scanning/analyses?ref=refs/heads/{}&per_page=1",
+                inputs.repository, default_branch
+            ),
+            None::<&()>,
+        )
+        .await;
+    drop(sem);
+    match analyses {
+        Ok(a) => match a.first() {
+            Some(latest) => {
+                let age = (Utc::now() - latest.created_at).num_days();
+                if age > max_age_days {
+                    results.push(CheckResult::Failure(format!(
+                        "The latest code scanning analysis of `{}` is {} days old, older than {} days.",
+                        default_branch, age, max_age_days
+                    )));
+                } else {
+                    results.push(CheckResult::Pass(format!(
+                        "`{}` was last analysed {} days ago",
+                        default_branch, age
+                    )));
+                }
+            }
+            None => results.push(CheckResult::Failure(format!(
+                "No code scanning analysis has been uploaded for `{}`.",
+                default_branch
+            ))),
+        },
+        Err(e) => results.push(CheckResult::Failure(format!(
+            "Could not list code scanning analyses: {}",
+            request_failure_reason(&e)
+        ))),
+    }
+
+    let ignore_languages: Vec<String> = inputs
+        .config
+        .option("code_scanning", "ignore_languages")
+        .unwrap_or_default();
+    match (configured_languages, paths) {
+        (_, Err(reason)) => results.push(CheckResult::Notice(format!(
+            "Could not list repository files, so language coverage was not checked: {}",
+            reason
+        ))),
+        (Some(configured), Ok(paths)) => {
+            let missing: Vec<&str> = detect_languages(&paths, &ignore_languages)
+                .into_iter()
+                .filter(|l| !configured.contains(*l))
+                .collect();
+            if missing.is_empty() {
+                results.push(CheckResult::Pass(
+                    "Every detected language is analysed by CodeQL".to_owned(),
+                ));
+            }
+            results.extend(missing.into_iter().map(|l| {
+                CheckResult::Failure(format!(
+                    "`{}` code was found but is not analysed by CodeQL.",
+                    l
+                ))
+            }));
+        }
+        (None, Ok(_)) => results.push(CheckResult::Notice(
+            "Could not work out which languages CodeQL analyses, so language coverage was not checked.".to_owned(),
+        )),
+    }
+    results
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use http::request::Builder;
+use http_body_util::BodyExt;
+use octocrab::Octocrab;
+use serde::Deserialize;
+
+use crate::{
+    checks::{Check, CheckCategory},
+    github_utils::{
+        grab_first_file, list_tree, octocrab_with_access_token_for, octocrab_with_token_for,
+        request_failure_reason, GrabFileResult, RateThrottle,
+    },
+    glob_utils::glob_matches,
+    inputs::Inputs,
+    results::{CheckResult, Location},
+};
+
+pub(crate) struct CodeOwners;
+
+#[async_trait]
+impl Check for CodeOwners {
+    fn id(&self) -> &'static str {
+        "codeowners"
+    }
+
+    fn title(&self) -> &'static str {
+        "CODEOWNERS File"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::CodeOwners
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add a valid `.github/CODEOWNERS` file whose owners have write access, and give every important path an owner."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_codeowners(requests, inputs).await
+    }
+}
+
+// In the order GitHub looks for them; only the first one found is used.
+static CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+static DEFAULT_IMPORTANT_PATHS: [&str; 3] = [
+    ".github/workflows/",
+    ".github/dependabot.yml",
+    "Gemfile.lock",
+];
+
+struct OwnerRule {
+    line: usize,
+    pattern: String,
+    owners: Vec<String>,
+}
+
+impl OwnerRule {
+    /// Matches a file path the way GitHub does: patterns containing a slash are anchored to the
+    /// root, and a pattern naming a directory matches everything beneath it.  A wildcard in the
+    /// last segment, as in `docs/*`, only matches the files directly inside.
+    fn matches(&self, path: &str) -> bool {
+        let trimmed = self.pattern.trim_end_matches('/');
+        let anchored = trimmed.contains('/');
+        let mut pattern: Vec<&str> = trimmed.split('/').filter(|s| !s.is_empty()).collect();
+        if !anchored {
+            pattern.insert(0, "**");
+        }
+        let path: Vec<&str> = path.split('/').collect();
+        let directory =
+            self.pattern.ends_with('/') || pattern.last().is_some_and(|last| !last.contains('*'));
+        let mut beneath = pattern.clone();
+        beneath.push("**");
+        (!self.pattern.ends_with('/') && glob_matches(&pattern, &path))
+            || (directory && glob_matches(&beneath, &path))
+    }
+}
+
+fn valid_owner(owner: &str) -> bool {
+    match owner.strip_prefix('@') {
+        Some(name) => match name.split_once('/') {
+            Some((org, team)) => !org.is_empty() && !team.is_empty() && !team.contains('/'),
+            None => !name.is_empty(),
+        },
+        None => owner
+            .split_once('@')
+            .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.')),
+    }
+}
+
+/// Parses the rules of a CODEOWNERS file, along with a located failure for every syntax error.
+fn parse_codeowners(path: &str, text: &str) -> (Vec<OwnerRule>, Vec<CheckResult>) {
+    let mut rules = Vec::new();
+    let mut errors = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split_once(" #").map(|(l, _)| l).unwrap_or(line).trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let location = Location::line(path, i + 1);
+        let mut tokens = line.split_whitespace();
+        let pattern = tokens.next().unwrap_or_default().to_owned();
+        let owners: Vec<String> = tokens.map(|t| t.to_owned()).collect();
+        if pattern.starts_with('!') || pattern.contains('[') {
+            errors.push(
+                CheckResult::Failure(format!(
+                    "Unsupported pattern `{}`: CODEOWNERS does not support `!` negation or `[ ]` ranges.",
+                    pattern
+                ))
+                .at(location.clone()),
+            );
+            continue;
+        }
+        for owner in owners.iter().filter(|o| !valid_owner(o)) {
+            errors.push(
+                CheckResult::Failure(format!(
+                    "Invalid owner `{}`: owners must be `@user`, `@org/team` or an email address.",
+                    owner
+                ))
+                .at(location.clone()),
+            );
+        }
+        rules.push(OwnerRule {
+            line: i + 1,
+            pattern,
+            owners,
+        });
+    }
+    (rules, errors)
+}
+
+#[derive(Deserialize)]
+struct CollaboratorPermission {
+    permission: String,
+}
+
+#[derive(Deserialize)]
+struct TeamRepository {
+    permissions: TeamPermissions,
+}
+
+#[derive(Deserialize)]
+struct TeamPermissions {
+    #[serde(default)]
+    push: bool,
+}
+
+/// Why an owner can't own files in the repository, or `None` if it can.
+async fn owner_problem(oc: &Octocrab, inputs: &Inputs, owner: &str) -> Option<String> {
+    let name = owner.strip_prefix('@')?;
+    match name.split_once('/') {
+        None => {
+            let permission: Result<CollaboratorPermission, _> = oc
+                .get(
+                    format!(
+                        "/repos/{}/collaborators/{}/permission",
+                        inputs.repository, name
+                    ),
+                    None::<&()>,
+                )
+                .await;
+            match permission {
+                Ok(p) if p.permission == "admin" || p.permission == "write" => None,
+                Ok(_) => Some(format!("`{}` does not have write access.", owner)),
+                Err(octocrab::Error::GitHub { source, .. })
+                    if source.status_code.as_u16() == 404 =>
+                {
+                    Some(format!("`{}` does not exist.", owner))
+                }
+                Err(e) => Some(format!(
+                    "Could not check `{}`: {}",
+                    owner,
+                    request_failure_reason(&e)
+                )),
+            }
+        }
+        Some((org, team)) => {
+            let builder = Builder::new()
+                .uri(format!(
+                    "/orgs/{}/teams/{}/repos/{}",
+                    org, team, inputs.repository
+                ))
+                .header("Accept", "application/vnd.github.v3.repository+json")
+                .method(http::Method::GET);
+            let req = oc.build_request(builder, None::<&()>).unwrap();
+            match oc.execute(req).await {
+                Err(_) => Some(format!("Could not check `{}`: Request failure.", owner)),
+                Ok(x) if x.status().is_success() => {
+                    let body = x.into_body().collect().await.map(|b| b.to_bytes());
+                    match body
+                        .ok()
+                        .and_then(|b| serde_json::from_slice::<TeamRepository>(&b).ok())
+                    {
+                        Some(r) if r.permissions.push => None,
+                        _ => Some(format!("`{}` does not have write access.", owner)),
+                    }
+                }
+                Ok(x) if x.status().as_u16() == 404 => Some(format!(
+                    "`{}` does not exist or has no access to the repository.",
+                    owner
+                )),
+                Ok(x) => Some(format!(
+                    "Could not check `{}`: Endpoint returned {}.",
+                    owner,
+                    x.status().as_str()
+                )),
+            }
+        }
+    }
+}
+
+async fn verify_codeowners(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let oc = octocrab_with_token_for(&inputs);
+    let sem = requests.acquire().await;
+    let (file, path) = grab_first_file(&oc, &inputs, &CODEOWNERS_PATHS).await;
+    drop(sem);
+    let text = match file {
+        GrabFileResult::File(contents) => String::from_utf8_lossy(&contents).into_owned(),
+        GrabFileResult::AccessDenied => {
+            return vec![CheckResult::Failure(
+                "Could not find a CODEOWNERS file: Access denied.".to_owned(),
+            )]
+        }
+        GrabFileResult::AccessForbidden => {
+            return vec![CheckResult::Failure(
+                "Could not find a CODEOWNERS file: Access forbidden.".to_owned(),
+            )]
+        }
+        GrabFileResult::Error(_) => {
+            return vec![CheckResult::Failure(
+                "Could not find a CODEOWNERS file.".to_owned(),
+            )]
+        }
+        GrabFileResult::NotFound => {
+            return vec![CheckResult::Failure(
+                "Could not find a CODEOWNERS file in the root, `.github/` or `docs/`.".to_owned(),
+            )]
+        }
+    };
+
+    let (rules, mut results) = parse_codeowners(path, &text);
+
+    // Each owner is checked once, and reported at the first line using it.
+    let access_oc = octocrab_with_access_token_for(&inputs);
+    let mut checked: HashMap<&str, usize> = HashMap::new();
+    for rule in rules.iter() {
+        for owner in rule.owners.iter().filter(|o| valid_owner(o)) {
+            checked.entry(owner.as_str()).or_insert(rule.line);
+        }
+    }
+    let mut owners: Vec<(&str, usize)> = checked.into_iter().collect();
+    owners.sort_by_key(|(_, line)| *line);
+    for (owner, line) in owners {
+        let _permit = requests.acquire().await;
+        if let Some(problem) = owner_problem(&access_oc, &inputs, owner).await {
+            results.push(CheckResult::Failure(problem).at(Location::line(path, line)));
+        }
+    }
+
+    let important_paths: Vec<String> = inputs
+        .config
+        .option("codeowners", "important_paths")
+        .unwrap_or_else(|| DEFAULT_IMPORTANT_PATHS.map(|p| p.to_owned()).to_vec());
+    let sem = requests.acquire().await;
+    let files = match list_tree(&oc, &inputs).await {
+        Ok(f) => f,
+        Err(reason) => {
+            results.push(CheckResult::Failure(format!(
+                "Could not list repository files to check code owners: {}",
+                reason
+            )));
+            return results;
+        }
+    };
+    drop(sem);
+    for important in important_paths.iter() {
+        let unowned = files
+            .iter()
+            .filter(|f| {
+                *f == important || (important.ends_with('/') && f.starts_with(important.as_str()))
+            })
+            .find(|f| {
+                rules
+                    .iter()
+                    .rev()
+                    .find(|r| r.matches(f))
+                    .is_none_or(|r| r.owners.is_empty())
+            });
+        if let Some(f) = unowned {
+            results.push(
+                CheckResult::Warning(format!("`{}` has no code owner.", f))
+                    .at(Location::file(f.as_str())),
+            );
+        }
+    }
+
+    if results.is_empty() {
+        vec![CheckResult::Pass(format!("Found a valid `{}`", path)).at(Location::file(path))]
+    } else {
+        results
+    }
+}
@@ -1,16 +1,47 @@
+use async_trait::async_trait;
+
 use crate::{
+    checks::{Check, CheckCategory},
     github_utils::{
-        file_check, octocrab_repo_handler_for, octocrab_with_token_for, FileCheckResult,
+        first_file_check, octocrab_repo_handler_for, octocrab_with_token_for, FileCheckResult,
         RateThrottle,
     },
     inputs::Inputs,
     results::CheckResult,
 };
 
-pub(crate) async fn verify_copilot_yaml(
-    mut results: RateThrottle,
-    inputs: Inputs,
-) -> Vec<CheckResult> {
+pub(crate) struct CopilotIgnore;
+
+#[async_trait]
+impl Check for CopilotIgnore {
+    fn id(&self) -> &'static str {
+        "copilotignore"
+    }
+
+    fn title(&self) -> &'static str {
+        "Copilot Ignore File for Private Repositories"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Copilot
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add a `.copilotignore` file to the root of the repository."
+    }
+
+    fn location(&self) -> Option<&'static str> {
+        Some(".copilotignore")
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_copilot_yaml(requests, inputs).await
+    }
+}
+
+static COPILOTIGNORE_PATHS: [&str; 2] = [".copilotignore", ".github/.copilotignore"];
+
+async fn verify_copilot_yaml(mut results: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
     let oc = octocrab_with_token_for(&inputs);
     let rh = octocrab_repo_handler_for(&oc, &inputs);
     let sem = results.acquire().await;
@@ -19,11 +50,13 @@ pub(crate) async fn verify_copilot_yaml(
         return vec![CheckResult::Ignore];
     }
     drop(sem);
-    let _ = results.acquire().await;
-    match file_check(&oc, &inputs, ".copilotignore").await {
-        FileCheckResult::Found => vec![CheckResult::Pass(
-            "Found a `.copilotignore` file for a private repository.".to_owned(),
-        )],
+    let _permit = results.acquire().await;
+    let (file, path) = first_file_check(&oc, &inputs, &COPILOTIGNORE_PATHS).await;
+    match file {
+        FileCheckResult::Found => vec![CheckResult::Pass(format!(
+            "Found a `{}` file for a private repository.",
+            path
+        ))],
         FileCheckResult::AccessDenied => vec![CheckResult::Failure(
             "Could not find a .copilotignore file for a private repository: Access Denied"
                 .to_owned(),
@@ -1,38 +1,108 @@
+use async_trait::async_trait;
 use http::request::Builder;
+use http_body_util::BodyExt;
+use serde::Deserialize;
 
 use crate::{
+    checks::{
+        dependabot_yaml::{interval_rank, DependabotYaml, DEPENDABOT_YAML_PATHS},
+        Check, CheckCategory,
+    },
+    config::CONFIG_FILE_PATH,
     github_utils::{
-        file_check, octocrab_with_access_token_for, octocrab_with_token_for, FileCheckResult,
-        RateThrottle,
+        grab_first_file, list_tree, octocrab_with_access_token_for, octocrab_with_token_for,
+        GrabFileResult, RateThrottle,
     },
+    glob_utils::glob_matches,
     inputs::Inputs,
-    results::CheckResult,
+    results::{CheckResult, Location},
 };
 
-pub(crate) async fn verify_dependabot(requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
-    vec![
-        verify_dependabot_enabled(requests.clone(), inputs.clone()).await,
-        verify_dependabot_yaml(requests, inputs.clone()).await,
-    ]
+pub(crate) struct DependabotEnabled;
+
+#[async_trait]
+impl Check for DependabotEnabled {
+    fn id(&self) -> &'static str {
+        "dependabot_enabled"
+    }
+
+    fn title(&self) -> &'static str {
+        "Dependabot Enabled"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Dependabot
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Enable Dependabot alerts under the repository's Code security settings."
+    }
+
+    fn reads_commit(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        vec![verify_dependabot_enabled(requests, inputs).await]
+    }
+}
+
+pub(crate) struct DependabotConfig;
+
+#[async_trait]
+impl Check for DependabotConfig {
+    fn id(&self) -> &'static str {
+        "dependabot_config"
+    }
+
+    fn title(&self) -> &'static str {
+        "Dependabot Configuration File"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Dependabot
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add a valid `.github/dependabot.yml` (or `.yaml`) file describing the package ecosystems to update."
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_dependabot_yaml(requests, inputs).await
+    }
 }
 
-async fn verify_dependabot_yaml(mut requests: RateThrottle, inputs: Inputs) -> CheckResult {
+async fn verify_dependabot_yaml(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
     let oc = octocrab_with_token_for(&inputs);
-    let _ = requests.acquire().await;
-    match file_check(&oc, &inputs, ".github/dependabot.yml").await {
-        FileCheckResult::Found => CheckResult::Pass("Found a `.github/dependabot.yml`".to_owned()),
-        FileCheckResult::AccessDenied => CheckResult::Failure(
-            "Could not find a .github/dependabot.yml file: Access Denied".to_owned(),
-        ),
-        FileCheckResult::AccessForbidden => CheckResult::Failure(
-            "Could not find a .github/dependabot.yml file: Access forbidden.".to_owned(),
-        ),
-        FileCheckResult::Error(_) => {
-            CheckResult::Failure("Could not find a .github/dependabot.yml file.".to_owned())
-        }
-        FileCheckResult::NotFound => {
-            CheckResult::Failure("Could not find a .github/dependabot.yml file.".to_owned())
+    let _permit = requests.acquire().await;
+    let (file, path) = grab_first_file(&oc, &inputs, &DEPENDABOT_YAML_PATHS).await;
+    match file {
+        GrabFileResult::File(contents) => {
+            let errors = match DependabotYaml::parse(path, &contents) {
+                Ok(dy) => dy.validate(),
+                Err(e) => vec![e],
+            };
+            if errors.is_empty() {
+                vec![CheckResult::Pass(format!("Found a valid `{}`", path)).at(Location::file(path))]
+            } else {
+                errors
+            }
         }
+        GrabFileResult::AccessDenied => vec![CheckResult::Failure(format!(
+            "Could not find a `{}` file: Access Denied",
+            path
+        ))],
+        GrabFileResult::AccessForbidden => vec![CheckResult::Failure(format!(
+            "Could not find a `{}` file: Access forbidden.",
+            path
+        ))],
+        GrabFileResult::Error(_) => vec![CheckResult::Failure(format!(
+            "Could not find a `{}` file.",
+            path
+        ))],
+        GrabFileResult::NotFound => vec![CheckResult::Failure(
+            "Could not find a .github/dependabot.yml or .github/dependabot.yaml file.".to_owned(),
+        )],
     }
 }
 
@@ -42,7 +112,7 @@ async fn verify_dependabot_enabled(mut requests: RateThrottle, inputs: Inputs) -
         .uri("/repos/".to_owned() + &inputs.repository + "/vulnerability-alerts")
         .method(http::Method::GET);
     let req = oc.build_request(builder, None::<&()>).unwrap();
-    let _ = requests.acquire().await;
+    let _permit = requests.acquire().await;
     let dependabot_check = oc.execute(req).await;
     match dependabot_check {
         Err(_) => CheckResult::Failure(
@@ -68,3 +138,422 @@ async fn verify_dependabot_enabled(mut requests: RateThrottle, inputs: Inputs) -
         }
     }
 }
+
+pub(crate) struct DependabotCoverage;
+
+#[async_trait]
+impl Check for DependabotCoverage {
+    fn id(&self) -> &'static str {
+        "dependabot_coverage"
+    }
+
+    fn title(&self) -> &'static str {
+        "Dependabot Ecosystem Coverage"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Dependabot
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add an `updates` entry to `.github/dependabot.yml` for each package ecosystem and directory in the repository."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_dependabot_coverage(requests, inputs).await
+    }
+}
+
+/// A manifest found in the repository, and the Dependabot ecosystem and directory it needs.
+struct Manifest {
+    ecosystem: &'static str,
+    directory: String,
+    path: String,
+}
+
+fn ecosystem_for(path: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    if path.starts_with(".github/workflows/")
+        && (file_name.ends_with(".yml") || file_name.ends_with(".yaml"))
+    {
+        return Some("github-actions");
+    }
+    match file_name {
+        "Gemfile" | "Gemfile.lock" => Some("bundler"),
+        "package.json" => Some("npm"),
+        "requirements.txt" | "Pipfile" | "pyproject.toml" | "setup.py" => Some("pip"),
+        "go.mod" => Some("gomod"),
+        "Cargo.toml" => Some("cargo"),
+        "composer.json" => Some("composer"),
+        "pom.xml" => Some("maven"),
+        "build.gradle" | "build.gradle.kts" => Some("gradle"),
+        "mix.exs" => Some("mix"),
+        "pubspec.yaml" => Some("pub"),
+        "Package.swift" => Some("swift"),
+        "packages.config" => Some("nuget"),
+        "devcontainer.json" => Some("devcontainers"),
+        "docker-compose.yml" | "docker-compose.yaml" | "compose.yml" | "compose.yaml" => {
+            Some("docker-compose")
+        }
+        f if f == "Dockerfile" || f.starts_with("Dockerfile.") || f.ends_with(".dockerfile") => {
+            Some("docker")
+        }
+        f if f.ends_with(".gemspec") => Some("bundler"),
+        f if f.ends_with(".csproj") => Some("nuget"),
+        f if f.ends_with(".tf") => Some("terraform"),
+        _ => None,
+    }
+}
+
+fn normalize_directory(directory: &str) -> String {
+    format!("/{}", directory.trim_matches('/'))
+}
+
+fn detect_manifests(paths: &[String], ignore_paths: &[String]) -> Vec<Manifest> {
+    let mut manifests: Vec<Manifest> = Vec::new();
+    for path in paths {
+        if path
+            .split('/')
+            .any(|s| s == "node_modules" || s == "vendor")
+            || ignore_paths
+                .iter()
+                .any(|i| path.starts_with(i.trim_start_matches('/')))
+        {
+            continue;
+        }
+        let ecosystem = match ecosystem_for(path) {
+            Some(e) => e,
+            None => continue,
+        };
+        // Workflows and dev containers are always configured from the repository root.
+        let directory = match ecosystem {
+            "github-actions" | "devcontainers" => "/".to_owned(),
+            _ => normalize_directory(path.rsplit_once('/').map(|(d, _)| d).unwrap_or("")),
+        };
+        if !manifests
+            .iter()
+            .any(|m| m.ecosystem == ecosystem && m.directory == directory)
+        {
+            manifests.push(Manifest {
+                ecosystem,
+                directory,
+                path: path.clone(),
+            });
+        }
+    }
+    manifests
+}
+
+fn directory_matches(pattern: &str, directory: &str) -> bool {
+    let pattern = normalize_directory(pattern);
+    let pattern_segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let directory_segments: Vec<&str> = directory.split('/').filter(|s| !s.is_empty()).collect();
+    glob_matches(&pattern_segments, &directory_segments)
+}
+
+async fn verify_dependabot_coverage(
+    mut requests: RateThrottle,
+    inputs: Inputs,
+) -> Vec<CheckResult> {
+    let oc = octocrab_with_token_for(&inputs);
+    let sem = requests.acquire().await;
+    let paths = match list_tree(&oc, &inputs).await {
+        Ok(p) => p,
+        Err(reason) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not list repository files to check Dependabot coverage: {}",
+                reason
+            ))]
+        }
+    };
+    drop(sem);
+    let ignore_paths: Vec<String> = inputs
+        .config
+        .option("dependabot_coverage", "ignore_paths")
+        .unwrap_or_default();
+    let manifests = detect_manifests(&paths, &ignore_paths);
+    if manifests.is_empty() {
+        return vec![CheckResult::Ignore];
+    }
+
+    let _permit = requests.acquire().await;
+    let (updates, config_path) = match grab_first_file(&oc, &inputs, &DEPENDABOT_YAML_PATHS).await {
+        (GrabFileResult::File(contents), path) => match DependabotYaml::parse(path, &contents) {
+            Ok(dy) => (dy.updates(), Some(path)),
+            Err(e) => return vec![e],
+        },
+        (GrabFileResult::NotFound, _) => (Vec::new(), None),
+        (_, path) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not read `{}` to check coverage: Request failure.",
+                path
+            ))]
+        }
+    };
+
+    let uncovered: Vec<CheckResult> = manifests
+        .iter()
+        .filter(|m| {
+            !updates.iter().any(|u| {
+                u.ecosystem == m.ecosystem
+                    && u.directories
+                        .iter()
+                        .any(|d| directory_matches(d, &m.directory))
+            })
+        })
+        .map(|m| {
+            CheckResult::Failure(format!(
+                "Dependabot does not update `{}` dependencies in `{}` (found `{}`).",
+                m.ecosystem, m.directory, m.path
+            ))
+            .at(Location::file(m.path.as_str()))
+        })
+        .collect();
+    if uncovered.is_empty() {
+        let pass = CheckResult::Pass(format!(
+            "Dependabot covers all {} detected package ecosystem directories.",
+            manifests.len()
+        ));
+        match config_path {
+            Some(path) => vec![pass.at(Location::file(path))],
+            None => vec![pass],
+        }
+    } else {
+        uncovered
+    }
+}
+
+pub(crate) struct DependabotSecurityUpdates;
+
+#[async_trait]
+impl Check for DependabotSecurityUpdates {
+    fn id(&self) -> &'static str {
+        "dependabot_security_updates"
+    }
+
+    fn title(&self) -> &'static str {
+        "Dependabot Security Updates Enabled"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Dependabot
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Enable Dependabot security updates under the repository's Code security settings."
+    }
+
+    fn reads_commit(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        vec![verify_dependabot_security_updates(requests, inputs).await]
+    }
+}
+
+#[derive(Deserialize)]
+struct AutomatedSecurityFixes {
+    enabled: bool,
+    paused: bool,
+}
+
+async fn verify_dependabot_security_updates(
+    mut requests: RateThrottle,
+    inputs: Inputs,
+) -> CheckResult {
+    let oc = octocrab_with_access_token_for(&inputs);
+    let builder = Builder::new()
+        .uri("/repos/".to_owned() + &inputs.repository + "/automated-security-fixes")
+        .method(http::Method::GET);
+    let req = oc.build_request(builder, None::<&()>).unwrap();
+    let _permit = requests.acquire().await;
+    match oc.execute(req).await {
+        Err(_) => CheckResult::Failure(
+            "Could not check if Dependabot security updates were enabled: Request failure."
+                .to_owned(),
+        ),
+        Ok(x) => {
+            if x.status().is_success() {
+                let body = x.into_body().collect().await.map(|b| b.to_bytes());
+                match body.ok().and_then(|b| {
+                    serde_json::from_slice::<AutomatedSecurityFixes>(&b).ok()
+                }) {
+                    Some(f) if f.enabled && !f.paused => {
+                        CheckResult::Pass("Dependabot security updates are enabled".to_owned())
+                    }
+                    Some(f) if f.enabled => {
+                        CheckResult::Failure("Dependabot security updates are paused.".to_owned())
+                    }
+                    Some(_) => CheckResult::Failure(
+                        "Dependabot security updates are not enabled.".to_owned(),
+                    ),
+                    None => CheckResult::Failure(
+                        "Could not check if Dependabot security updates were enabled: Unexpected response."
+                            .to_owned(),
+                    ),
+                }
+            } else if x.status().as_u16() == 401 {
+                CheckResult::Failure(
+                    "Could not check if Dependabot security updates were enabled: Access denied."
+                        .to_owned(),
+                )
+            } else if x.status().as_u16() == 403 {
+                CheckResult::Failure(
+                    "Could not check if Dependabot security updates were enabled: Access forbidden."
+                        .to_owned(),
+                )
+            } else {
+                CheckResult::Failure(format!(
+                    "Dependabot security updates not enabled.  Endpoint returned {}.",
+                    x.status().as_str()
+                ))
+            }
+        }
+    }
+}
+
+pub(crate) struct DependabotPolicy;
+
+#[async_trait]
+impl Check for DependabotPolicy {
+    fn id(&self) -> &'static str {
+        "dependabot_policy"
+    }
+
+    fn title(&self) -> &'static str {
+        "Dependabot Update Policy"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Dependabot
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Adjust the schedule, `open-pull-requests-limit` and `groups` of each `updates` entry in `.github/dependabot.yml` to meet the configured policy."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_dependabot_policy(requests, inputs).await
+    }
+}
+
+async fn verify_dependabot_policy(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let max_interval: Option<String> = inputs
+        .config
+        .option("dependabot_policy", "max_schedule_interval");
+    let min_pr_limit: Option<u64> = inputs
+        .config
+        .option("dependabot_policy", "min_open_pull_requests_limit");
+    let max_pr_limit: Option<u64> = inputs
+        .config
+        .option("dependabot_policy", "max_open_pull_requests_limit");
+    let require_groups_for: Vec<String> = inputs
+        .config
+        .option("dependabot_policy", "require_groups_for")
+        .unwrap_or_default();
+    if max_interval.is_none()
+        && min_pr_limit.is_none()
+        && max_pr_limit.is_none()
+        && require_groups_for.is_empty()
+    {
+        return vec![CheckResult::Ignore];
+    }
+    let max_rank = match max_interval.as_deref() {
+        Some(i) => match interval_rank(i) {
+            Some(rank) => Some(rank),
+            None => {
+                return vec![CheckResult::Failure(format!(
+                    "The `max_schedule_interval` option `{}` is not a schedule interval, such as `daily`, `weekly` or `monthly`.",
+                    i
+                ))
+                .at(Location::file(CONFIG_FILE_PATH))]
+            }
+        },
+        None => None,
+    };
+
+    let oc = octocrab_with_token_for(&inputs);
+    let _permit = requests.acquire().await;
+    let (dy, path) = match grab_first_file(&oc, &inputs, &DEPENDABOT_YAML_PATHS).await {
+        (GrabFileResult::File(contents), path) => match DependabotYaml::parse(path, &contents) {
+            Ok(dy) => (dy, path),
+            Err(e) => return vec![e],
+        },
+        (GrabFileResult::NotFound, _) => return vec![CheckResult::Ignore],
+        (_, path) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not read `{}` to check the update policy: Request failure.",
+                path
+            ))]
+        }
+    };
+
+    let mut results = Vec::new();
+    for update in dy.updates() {
+        let location = Location::line(path, update.line);
+        let update_rank = update.interval.as_deref().and_then(interval_rank);
+        if let (Some(max), Some(rank)) = (max_rank, update_rank) {
+            if rank > max {
+                results.push(
+                    CheckResult::Failure(format!(
+                        "`{}` updates run {}, less often than the required {}.",
+                        update.ecosystem,
+                        update.interval.as_deref().unwrap_or_default(),
+                        max_interval.as_deref().unwrap_or_default()
+                    ))
+                    .at(location.clone()),
+                );
+            }
+        }
+        // Dependabot's own default is 5 open pull requests.
+        let limit = update.open_pull_requests_limit.unwrap_or(5);
+        if let Some(max) = max_pr_limit {
+            if limit > max {
+                results.push(
+                    CheckResult::Failure(format!(
+                        "`{}` updates allow {} open pull requests, more than the allowed {}.",
+                        update.ecosystem, limit, max
+                    ))
+                    .at(location.clone()),
+                );
+            }
+        }
+        if let Some(min) = min_pr_limit {
+            if limit < min {
+                results.push(
+                    CheckResult::Failure(format!(
+                        "`{}` updates allow {} open pull requests, fewer than the required {}.",
+                        update.ecosystem, limit, min
+                    ))
+                    .at(location.clone()),
+                );
+            }
+        }
+        if !update.has_groups && require_groups_for.contains(&update.ecosystem) {
+            results.push(
+                CheckResult::Failure(format!(
+                    "`{}` updates must define `groups`.",
+                    update.ecosystem
+                ))
+                .at(location),
+            );
+        }
+    }
+    if results.is_empty() {
+        vec![
+            CheckResult::Pass(format!("`{}` meets the Dependabot update policy.", path))
+                .at(Location::file(path)),
+        ]
+    } else {
+        results
+    }
+}
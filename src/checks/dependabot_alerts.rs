@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use octocrab::Page;
+use serde::Deserialize;
+
+use crate::{
+    checks::{Check, CheckCategory},
+    github_utils::{octocrab_with_access_token_for, request_failure_reason, RateThrottle},
+    inputs::Inputs,
+    results::{CheckResult, Location},
+};
+
+pub(crate) struct DependabotAlerts;
+
+#[async_trait]
+impl Check for DependabotAlerts {
+    fn id(&self) -> &'static str {
+        "dependabot_alerts"
+    }
+
+    fn title(&self) -> &'static str {
+        "Open Dependabot Alerts Within SLA"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Dependabot
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Upgrade or remove the affected dependencies, or dismiss alerts which do not apply, before they exceed their SLA."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn reads_commit(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_dependabot_alerts(requests, inputs).await
+    }
+}
+
+#[derive(Deserialize)]
+struct DependabotAlert {
+    number: u64,
+    html_url: String,
+    created_at: DateTime<Utc>,
+    dependency: AlertDependency,
+    security_advisory: AlertAdvisory,
+}
+
+#[derive(Deserialize)]
+struct AlertDependency {
+    package: AlertPackage,
+    manifest_path: String,
+}
+
+#[derive(Deserialize)]
+struct AlertPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct AlertAdvisory {
+    severity: String,
+}
+
+struct OverdueAlert {
+    alert: DependabotAlert,
+    age: i64,
+    sla: i64,
+}
+
+fn default_sla_days() -> HashMap<String, i64> {
+    HashMap::from([("critical".to_owned(), 7), ("high".to_owned(), 30)])
+}
+
+fn overdue_table(overdue: &[OverdueAlert]) -> String {
+    let mut md = String::from(
+        "| Package | Ecosystem | Severity | Manifest | Age (days) | SLA (days) | Alert |\n|-----|-----|-----|-----|-----|-----|-----|\n",
+    );
+    for o in overdue {
+        md.push_str(&format!(
+            "| {} | {} | {} | `{}` | {} | {} | [#{}]({}) |\n",
+            o.alert.dependency.package.name,
+            o.alert.dependency.package.ecosystem,
+            o.alert.security_advisory.severity,
+            o.alert.dependency.manifest_path,
+            o.age,
+            o.sla,
+            o.alert.number,
+            o.alert.html_url
+        ));
+    }
+    md
+}
+
+async fn verify_dependabot_alerts(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let sla_days: HashMap<String, i64> = inputs
+        .config
+        .option("dependabot_alerts", "sla_days")
+        .unwrap_or_else(default_sla_days);
+    let oc = octocrab_with_access_token_for(&inputs);
+    let sem = requests.acquire().await;
+    let alerts = match oc
+        .get::<Page<DependabotAlert>, _, _>(
+            format!(
+                "/repos/{}/dependabot/alerts?state=open&per_page=100",
+                inputs.repository
+            ),
+            None::<&()>,
+        )
+        .await
+    {
+        Ok(page) => oc.all_pages(page).await,
+        Err(e) => Err(e),
+    };
+    drop(sem);
+    let alerts = match alerts {
+        Ok(a) => a,
+        Err(e) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not list open Dependabot alerts: {}",
+                request_failure_reason(&e)
+            ))]
+        }
+    };
+
+    let now = Utc::now();
+    let alert_count = alerts.len();
+    let mut overdue: Vec<OverdueAlert> = alerts
+        .into_iter()
+        .filter_map(|alert| {
+            let sla = *sla_days.get(&alert.security_advisory.severity.to_lowercase())?;
+            let age = (now - alert.created_at).num_days();
+            (age > sla).then_some(OverdueAlert { alert, age, sla })
+        })
+        .collect();
+    if overdue.is_empty() {
+        return vec![CheckResult::Pass(format!(
+            "{} open Dependabot alerts, none past their SLA.",
+            alert_count
+        ))];
+    }
+
+    overdue.sort_by_key(|o| std::cmp::Reverse(o.age));
+    let mut results: Vec<CheckResult> = overdue
+        .iter()
+        .map(|o| {
+            CheckResult::Failure(format!(
+                "`{}` has a {} Dependabot alert open for {} days, past the {} day SLA.",
+                o.alert.dependency.package.name, o.alert.security_advisory.severity, o.age, o.sla
+            ))
+            .at(Location::file(o.alert.dependency.manifest_path.clone()))
+        })
+        .collect();
+    results.push(CheckResult::Details(overdue_table(&overdue)));
+    results
+}
@@ -0,0 +1,268 @@
+use serde_yaml::Value;
+
+use crate::{
+    results::{CheckResult, Location},
+    yaml_utils::{key_line_between, sequence_item_lines, top_level_key_line},
+};
+
+pub(crate) static DEPENDABOT_YAML_PATHS: [&str; 2] =
+    [".github/dependabot.yml", ".github/dependabot.yaml"];
+
+static PACKAGE_ECOSYSTEMS: [&str; 23] = [
+    "bun",
+    "bundler",
+    "cargo",
+    "composer",
+    "devcontainers",
+    "docker",
+    "docker-compose",
+    "dotnet-sdk",
+    "elm",
+    "gitsubmodule",
+    "github-actions",
+    "gomod",
+    "gradle",
+    "helm",
+    "maven",
+    "mix",
+    "npm",
+    "nuget",
+    "pip",
+    "pub",
+    "swift",
+    "terraform",
+    "uv",
+];
+
+static SCHEDULE_INTERVALS: [&str; 7] = [
+    "daily",
+    "weekly",
+    "monthly",
+    "quarterly",
+    "semiannually",
+    "yearly",
+    "cron",
+];
+
+/// A single entry from the `updates` list.
+pub(crate) struct UpdateEntry {
+    pub(crate) ecosystem: String,
+    pub(crate) directories: Vec<String>,
+    pub(crate) interval: Option<String>,
+    pub(crate) open_pull_requests_limit: Option<u64>,
+    pub(crate) has_groups: bool,
+    pub(crate) line: usize,
+}
+
+/// Orders schedule intervals from most to least frequent.  `cron` schedules have no rank.
+pub(crate) fn interval_rank(interval: &str) -> Option<usize> {
+    SCHEDULE_INTERVALS
+        .iter()
+        .position(|i| *i == interval)
+        .filter(|_| interval != "cron")
+}
+
+/// A parsed Dependabot configuration, along with its source so errors can be given line numbers.
+pub(crate) struct DependabotYaml {
+    pub(crate) path: String,
+    text: String,
+    value: Value,
+}
+
+impl DependabotYaml {
+    pub(crate) fn parse(path: &str, contents: &[u8]) -> Result<Self, CheckResult> {
+        let text = String::from_utf8_lossy(contents).into_owned();
+        match serde_yaml::from_str(&text) {
+            Ok(value) => Ok(DependabotYaml {
+                path: path.to_owned(),
+                text,
+                value,
+            }),
+            Err(e) => {
+                let line = e.location().map(|l| l.line()).unwrap_or(1);
+                Err(
+                    CheckResult::Failure(format!("Could not parse `{}` as YAML: {}", path, e))
+                        .at(Location::line(path, line)),
+                )
+            }
+        }
+    }
+
+    pub(crate) fn updates(&self) -> Vec<UpdateEntry> {
+        let item_lines = sequence_item_lines(&self.text, "updates");
+        let updates = match self.value.get("updates").and_then(|u| u.as_sequence()) {
+            Some(u) => u,
+            None => return Vec::new(),
+        };
+        updates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, u)| {
+                let mut directories: Vec<String> = u
+                    .get("directories")
+                    .and_then(|d| d.as_sequence())
+                    .map(|ds| {
+                        ds.iter()
+                            .filter_map(|d| d.as_str().map(|d| d.to_owned()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if let Some(d) = u.get("directory").and_then(|d| d.as_str()) {
+                    directories.push(d.to_owned());
+                }
+                Some(UpdateEntry {
+                    ecosystem: u.get("package-ecosystem")?.as_str()?.to_owned(),
+                    directories,
+                    interval: u
+                        .get("schedule")
+                        .and_then(|s| s.get("interval"))
+                        .and_then(|i| i.as_str())
+                        .map(|i| i.to_owned()),
+                    open_pull_requests_limit: u
+                        .get("open-pull-requests-limit")
+                        .and_then(|l| l.as_u64()),
+                    has_groups: u.get("groups").is_some_and(|g| g.is_mapping()),
+                    line: item_lines.get(i).map(|l| l.0).unwrap_or(1),
+                })
+            })
+            .collect()
+    }
+
+    fn error(&self, line: usize, message: String) -> CheckResult {
+        CheckResult::Failure(format!("Invalid `{}`: {}", self.path, message))
+            .at(Location::line(self.path.as_str(), line))
+    }
+
+    /// Checks the file against the parts of the Dependabot v2 schema every update needs.
+    pub(crate) fn validate(&self) -> Vec<CheckResult> {
+        let mut errors = Vec::new();
+        if !self.value.is_mapping() {
+            errors.push(self.error(1, "the file must be a mapping.".to_owned()));
+            return errors;
+        }
+        let version_line = top_level_key_line(&self.text, "version").unwrap_or(1);
+        match self.value.get("version") {
+            None => errors.push(self.error(1, "`version` is missing.".to_owned())),
+            Some(v) if v.as_u64() == Some(2) => (),
+            Some(_) => errors.push(self.error(version_line, "`version` must be `2`.".to_owned())),
+        }
+        let updates_line = top_level_key_line(&self.text, "updates").unwrap_or(1);
+        let updates = match self.value.get("updates") {
+            None => {
+                errors.push(self.error(1, "`updates` is missing.".to_owned()));
+                return errors;
+            }
+            Some(Value::Sequence(u)) if !u.is_empty() => u,
+            Some(_) => {
+                errors.push(self.error(
+                    updates_line,
+                    "`updates` must be a non-empty list.".to_owned(),
+                ));
+                return errors;
+            }
+        };
+        let item_lines = sequence_item_lines(&self.text, "updates");
+        for (i, update) in updates.iter().enumerate() {
+            let (start, end) = item_lines
+                .get(i)
+                .copied()
+                .unwrap_or((updates_line, updates_line));
+            errors.extend(self.validate_update(i, update, start, end));
+        }
+        errors
+    }
+
+    fn validate_update(
+        &self,
+        index: usize,
+        update: &Value,
+        start: usize,
+        end: usize,
+    ) -> Vec<CheckResult> {
+        let mut errors = Vec::new();
+        let key_line = |key: &str| key_line_between(&self.text, key, start, end).unwrap_or(start);
+        if !update.is_mapping() {
+            errors.push(self.error(start, format!("`updates[{}]` must be a mapping.", index)));
+            return errors;
+        }
+        match update.get("package-ecosystem") {
+            None => errors.push(self.error(
+                start,
+                format!("`updates[{}]` is missing `package-ecosystem`.", index),
+            )),
+            Some(e) => match e.as_str() {
+                Some(name) if PACKAGE_ECOSYSTEMS.contains(&name) => (),
+                _ => errors.push(self.error(
+                    key_line("package-ecosystem"),
+                    format!(
+                        "`updates[{}].package-ecosystem` is not a supported package ecosystem.",
+                        index
+                    ),
+                )),
+            },
+        }
+        match (update.get("directory"), update.get("directories")) {
+            (None, None) => errors.push(self.error(
+                start,
+                format!(
+                    "`updates[{}]` is missing `directory` or `directories`.",
+                    index
+                ),
+            )),
+            (Some(_), Some(_)) => errors.push(self.error(
+                key_line("directories"),
+                format!(
+                    "`updates[{}]` can't have both `directory` and `directories`.",
+                    index
+                ),
+            )),
+            (Some(d), _) if !d.is_string() => errors.push(self.error(
+                key_line("directory"),
+                format!("`updates[{}].directory` must be a string.", index),
+            )),
+            (_, Some(d))
+                if !d
+                    .as_sequence()
+                    .is_some_and(|ds| !ds.is_empty() && ds.iter().all(|d| d.is_string())) =>
+            {
+                errors.push(self.error(
+                    key_line("directories"),
+                    format!(
+                        "`updates[{}].directories` must be a non-empty list of strings.",
+                        index
+                    ),
+                ))
+            }
+            _ => (),
+        }
+        match update.get("schedule") {
+            None => errors.push(self.error(
+                start,
+                format!("`updates[{}]` is missing `schedule`.", index),
+            )),
+            Some(s) => match s.get("interval").map(|i| i.as_str()) {
+                None => errors.push(self.error(
+                    key_line("schedule"),
+                    format!("`updates[{}].schedule` is missing `interval`.", index),
+                )),
+                Some(Some("cron")) if s.get("cronjob").is_none() => errors.push(self.error(
+                    key_line("interval"),
+                    format!(
+                        "`updates[{}].schedule` has a `cron` interval but no `cronjob`.",
+                        index
+                    ),
+                )),
+                Some(Some(i)) if SCHEDULE_INTERVALS.contains(&i) => (),
+                Some(_) => errors.push(self.error(
+                    key_line("interval"),
+                    format!(
+                        "`updates[{}].schedule.interval` must be one of {}.",
+                        index,
+                        SCHEDULE_INTERVALS.join(", ")
+                    ),
+                )),
+            },
+        }
+        errors
+    }
+}
@@ -1,9 +1,97 @@
+use std::fmt::Display;
+
+use async_trait::async_trait;
+
+use crate::{github_utils::RateThrottle, inputs::Inputs, results::CheckResult};
+
+pub(crate) mod actions_settings;
+
 pub(crate) mod branch_protection;
 
+pub(crate) mod code_scanning;
+
+pub(crate) mod codeowners;
+
 pub(crate) mod copilot;
 
 pub(crate) mod dependabot;
 
+pub(crate) mod dependabot_alerts;
+
+pub(crate) mod dependabot_yaml;
+
 pub(crate) mod rails_projects;
 
 pub(crate) mod quality;
+
+pub(crate) mod secret_scanning;
+
+pub(crate) mod security_policy;
+
+pub(crate) mod registry;
+
+pub(crate) mod workflow_hardening;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckCategory {
+    BranchProtection,
+    CodeOwners,
+    CodeScanning,
+    Configuration,
+    Copilot,
+    Dependabot,
+    Quality,
+    RailsProjects,
+    SecretScanning,
+    SecurityPolicy,
+    Workflows,
+}
+
+impl Display for CheckCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CheckCategory::BranchProtection => "branch_protection",
+            CheckCategory::CodeOwners => "code_owners",
+            CheckCategory::CodeScanning => "code_scanning",
+            CheckCategory::Configuration => "configuration",
+            CheckCategory::Copilot => "copilot",
+            CheckCategory::Dependabot => "dependabot",
+            CheckCategory::Quality => "quality",
+            CheckCategory::RailsProjects => "rails_projects",
+            CheckCategory::SecretScanning => "secret_scanning",
+            CheckCategory::SecurityPolicy => "security_policy",
+            CheckCategory::Workflows => "workflows",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single compliance check which can be enabled or disabled by its id.
+#[async_trait]
+pub(crate) trait Check: Send + Sync {
+    /// Stable identifier, used by inputs and configuration to refer to the check.
+    fn id(&self) -> &'static str;
+
+    fn title(&self) -> &'static str;
+
+    fn category(&self) -> CheckCategory;
+
+    /// What to do to make a failing check pass.
+    fn remediation(&self) -> &'static str;
+
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    /// The file findings are reported against when a result carries no location of its own.
+    fn location(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Whether results depend on the files at `inputs.sha`, rather than only on repository settings.
+    fn reads_commit(&self) -> bool {
+        true
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult>;
+}
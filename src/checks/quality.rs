@@ -1,18 +1,24 @@
+use async_trait::async_trait;
 use octocrab::{models::hooks::Hook, OctocrabBuilder};
 
-use crate::{github_utils::RateThrottle, inputs::Inputs, results::CheckResult};
+use crate::{
+    checks::{Check, CheckCategory},
+    github_utils::{file_check, octocrab_with_token_for, FileCheckResult, RateThrottle},
+    inputs::Inputs,
+    results::{CheckResult, Location},
+};
 
 static YELLR_WEBHOOK_1: &str =
     "https://us-central1-active-branches-report.cloudfunctions.net/webhook";
 static YELLR_WEBHOOK_2: &str = "https://yellr.app/webhook";
 
-fn hook_check(h: &Hook) -> bool {
+fn hook_check(h: &Hook, webhook_urls: &[String]) -> bool {
     let url = h.config.url.as_str();
     let correct_content_type = h
         .config
         .content_type
         .eq(&Some(octocrab::models::hooks::ContentType::Json));
-    let correct_url = url.eq(YELLR_WEBHOOK_1) || url.eq(YELLR_WEBHOOK_2);
+    let correct_url = webhook_urls.iter().any(|u| url.eq(u));
     let does_on_create = h
         .events
         .iter()
@@ -46,13 +52,43 @@ fn hook_check(h: &Hook) -> bool {
     correct_content_type && correct_url && h.active && does_correct_events
 }
 
-pub(crate) async fn verify_updates_yellr(
-    mut results: RateThrottle,
-    inputs: Inputs,
-) -> Vec<CheckResult> {
+pub(crate) struct UpdatesYellr;
+
+#[async_trait]
+impl Check for UpdatesYellr {
+    fn id(&self) -> &'static str {
+        "yellr"
+    }
+
+    fn title(&self) -> &'static str {
+        "Reports to Yellr"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Quality
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add an active JSON webhook for Yellr which sends create, delete, pull request, pull request review, push and workflow run events."
+    }
+
+    fn reads_commit(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_updates_yellr(requests, inputs).await
+    }
+}
+
+async fn verify_updates_yellr(mut results: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let webhook_urls: Vec<String> = inputs
+        .config
+        .option("yellr", "webhook_urls")
+        .unwrap_or_else(|| vec![YELLR_WEBHOOK_1.to_owned(), YELLR_WEBHOOK_2.to_owned()]);
     let ob = OctocrabBuilder::new().personal_token(inputs.access_token);
     let oc = ob.build().unwrap();
-    let _ = results.acquire().await;
+    let _permit = results.acquire().await;
     let dependabot_check: octocrab::Result<octocrab::Page<Hook>> = oc
         .get(
             "/repos/".to_owned() + &inputs.repository + "/hooks",
@@ -69,7 +105,7 @@ pub(crate) async fn verify_updates_yellr(
                 "Could not check if repository reports to Yellr: Request failure.".to_owned(),
             )],
             Ok(y) => {
-                if y.iter().any(hook_check) {
+                if y.iter().any(|h| hook_check(h, &webhook_urls)) {
                     vec![CheckResult::Pass(
                         "Repository Reports to Yellr correctly".to_owned(),
                     )]
@@ -82,3 +118,65 @@ pub(crate) async fn verify_updates_yellr(
         },
     }
 }
+
+pub(crate) struct RequiredFiles;
+
+#[async_trait]
+impl Check for RequiredFiles {
+    fn id(&self) -> &'static str {
+        "required_files"
+    }
+
+    fn title(&self) -> &'static str {
+        "Required Files"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Quality
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add each file listed in the `paths` option for this check."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_required_files(requests, inputs).await
+    }
+}
+
+async fn verify_required_files(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let paths: Vec<String> = inputs
+        .config
+        .option("required_files", "paths")
+        .unwrap_or_default();
+    if paths.is_empty() {
+        return vec![CheckResult::Ignore];
+    }
+    let oc = octocrab_with_token_for(&inputs);
+    let mut results = Vec::new();
+    for path in paths {
+        let _permit = requests.acquire().await;
+        let result = match file_check(&oc, &inputs, &path).await {
+            FileCheckResult::Found => CheckResult::Pass(format!("Found a `{}` file.", path)),
+            FileCheckResult::AccessDenied => {
+                CheckResult::Failure(format!("Could not find a `{}` file: Access Denied", path))
+            }
+            FileCheckResult::AccessForbidden => CheckResult::Failure(format!(
+                "Could not find a `{}` file: Access forbidden.",
+                path
+            )),
+            FileCheckResult::NotFound => {
+                CheckResult::Failure(format!("Could not find a `{}` file.", path))
+            }
+            FileCheckResult::Error(_) => {
+                CheckResult::Failure(format!("Could not find a `{}` file: request failed.", path))
+            }
+        };
+        results.push(result.at(Location::file(path)));
+    }
+    results
+}
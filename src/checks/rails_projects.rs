@@ -1,24 +1,48 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
 use octocrab::Octocrab;
+use tokio::sync::OnceCell;
 
 use crate::{
+    checks::{Check, CheckCategory},
     github_utils::{
-        file_check, grab_file, octocrab_with_token_for, FileCheckResult, GrabFileResult,
-        RateThrottle,
+        file_check, first_file_check, grab_file, list_tree, octocrab_with_token_for,
+        FileCheckResult, GrabFileResult, RateThrottle,
     },
     inputs::Inputs,
-    results::CheckResult,
+    results::{CheckResult, Location},
+    workflow_utils::{grab_repo_workflows, Workflow},
 };
 
-pub(crate) async fn verify_rails_projects(
-    requests: RateThrottle,
-    inputs: Inputs,
-) -> Vec<CheckResult> {
-    let oc = octocrab_with_token_for(&inputs);
-    let mut results = Vec::new();
-    if inputs.check_bundler_audit {
-        results.push(verify_bundler_audit(requests, &oc, &inputs).await);
+pub(crate) struct BundlerAudit;
+
+#[async_trait]
+impl Check for BundlerAudit {
+    fn id(&self) -> &'static str {
+        "bundler_audit"
+    }
+
+    fn title(&self) -> &'static str {
+        "Bundler Audit"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::RailsProjects
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add a `.bundler-audit.yml` file and run bundler-audit in CI."
+    }
+
+    fn location(&self) -> Option<&'static str> {
+        Some("Gemfile.lock")
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        let oc = octocrab_with_token_for(&inputs);
+        vec![verify_bundler_audit(requests, &oc, &inputs).await]
     }
-    results
 }
 
 async fn verify_bundler_audit(
@@ -27,10 +51,9 @@ async fn verify_bundler_audit(
     inputs: &Inputs,
 ) -> CheckResult {
     let sem = results.acquire().await;
-    let gl_file = grab_file(&oc, &inputs, "Gemfile.lock").await;
+    let gl_file = grab_file(oc, inputs, "Gemfile.lock").await;
+    let gem_file = grab_file(oc, inputs, "Gemfile").await;
     drop(sem);
-    let gem_file = grab_file(&oc, &inputs, "Gemfile").await;
-    let _ = results.acquire().await;
     match (gem_file, gl_file) {
         (GrabFileResult::NotFound, GrabFileResult::NotFound) => CheckResult::Ignore,
         (GrabFileResult::File(_), _) => check_for_bundler_audit_yaml(results, oc, inputs).await,
@@ -47,16 +70,17 @@ async fn verify_bundler_audit(
     }
 }
 
+static BUNDLER_AUDIT_PATHS: [&str; 2] = [".bundler-audit.yml", ".bundler-audit.yaml"];
+
 async fn check_for_bundler_audit_yaml(
     mut results: RateThrottle,
     oc: &Octocrab,
     inputs: &Inputs,
 ) -> CheckResult {
-    let _ = results.acquire().await;
-    match file_check(&oc, &inputs, ".bundler-audit.yml").await {
-        FileCheckResult::Found => {
-            CheckResult::Pass("Found a `.bundler-audit.yml` file.".to_owned())
-        }
+    let _permit = results.acquire().await;
+    let (file, path) = first_file_check(oc, inputs, &BUNDLER_AUDIT_PATHS).await;
+    match file {
+        FileCheckResult::Found => CheckResult::Pass(format!("Found a `{}` file.", path)),
         FileCheckResult::AccessDenied => CheckResult::Failure(
             "Could not find a `.bundler-audit.yml` file: Access Denied".to_owned(),
         ),
@@ -71,3 +95,370 @@ async fn check_for_bundler_audit_yaml(
         ),
     }
 }
+
+/// Whether `Gemfile.lock` depends on `rails`, worked out by the first Rails check to ask and
+/// shared with the others in the same run.  Anything other than a yes or a no is kept as the
+/// result for each of them to report.
+#[derive(Clone, Default)]
+pub(crate) struct RailsApp(Arc<OnceCell<Result<bool, CheckResult>>>);
+
+impl RailsApp {
+    async fn get(
+        &self,
+        requests: &mut RateThrottle,
+        oc: &Octocrab,
+        inputs: &Inputs,
+    ) -> Result<bool, CheckResult> {
+        self.0
+            .get_or_init(|| find_rails_app(requests, oc, inputs))
+            .await
+            .clone()
+    }
+}
+
+async fn find_rails_app(
+    requests: &mut RateThrottle,
+    oc: &Octocrab,
+    inputs: &Inputs,
+) -> Result<bool, CheckResult> {
+    let _permit = requests.acquire().await;
+    match grab_file(oc, inputs, "Gemfile.lock").await {
+        GrabFileResult::File(contents) => Ok(String::from_utf8_lossy(&contents)
+            .lines()
+            .any(|l| l.split_whitespace().next() == Some("rails"))),
+        GrabFileResult::NotFound => Ok(false),
+        GrabFileResult::AccessDenied => Err(CheckResult::Failure(
+            "Could not check for a Gemfile.lock file: Access denied.".to_owned(),
+        )),
+        GrabFileResult::AccessForbidden => Err(CheckResult::Failure(
+            "Could not check for a Gemfile.lock file: Access forbidden.".to_owned(),
+        )),
+        GrabFileResult::Error(_) => Err(CheckResult::Failure(
+            "Could not check for a Gemfile.lock file: Request failure.".to_owned(),
+        )),
+    }
+}
+
+pub(crate) struct RubyVersion(pub(crate) RailsApp);
+
+#[async_trait]
+impl Check for RubyVersion {
+    fn id(&self) -> &'static str {
+        "ruby_version"
+    }
+
+    fn title(&self) -> &'static str {
+        "Ruby Version Pinned"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::RailsProjects
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add a `.ruby-version` file, or a `ruby` directive to the Gemfile."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        vec![verify_ruby_version(requests, &inputs, &self.0).await]
+    }
+}
+
+async fn verify_ruby_version(
+    mut requests: RateThrottle,
+    inputs: &Inputs,
+    rails_app: &RailsApp,
+) -> CheckResult {
+    let oc = octocrab_with_token_for(inputs);
+    match rails_app.get(&mut requests, &oc, inputs).await {
+        Ok(true) => {}
+        Ok(false) => return CheckResult::Ignore,
+        Err(e) => return e,
+    }
+    let sem = requests.acquire().await;
+    let ruby_version = file_check(&oc, inputs, ".ruby-version").await;
+    drop(sem);
+    match ruby_version {
+        FileCheckResult::Found => {
+            return CheckResult::Pass("Found a `.ruby-version` file.".to_owned())
+        }
+        FileCheckResult::NotFound => {}
+        FileCheckResult::AccessDenied => {
+            return CheckResult::Failure(
+                "Could not check for a `.ruby-version` file: Access denied.".to_owned(),
+            )
+        }
+        FileCheckResult::AccessForbidden => {
+            return CheckResult::Failure(
+                "Could not check for a `.ruby-version` file: Access forbidden.".to_owned(),
+            )
+        }
+        FileCheckResult::Error(_) => {
+            return CheckResult::Failure(
+                "Could not check for a `.ruby-version` file: Request failure.".to_owned(),
+            )
+        }
+    }
+    let sem = requests.acquire().await;
+    let gem_file = grab_file(&oc, inputs, "Gemfile").await;
+    drop(sem);
+    match gem_file {
+        GrabFileResult::File(contents)
+            if String::from_utf8_lossy(&contents).lines().any(|l| {
+                let l = l.trim_start();
+                l.starts_with("ruby ") || l.starts_with("ruby(")
+            }) =>
+        {
+            CheckResult::Pass("The Gemfile has a `ruby` directive.".to_owned())
+        }
+        GrabFileResult::File(_) | GrabFileResult::NotFound => CheckResult::Failure(
+            "Could not find a `.ruby-version` file or a `ruby` directive in the Gemfile."
+                .to_owned(),
+        ),
+        GrabFileResult::AccessDenied => {
+            CheckResult::Failure("Could not read the Gemfile: Access denied.".to_owned())
+        }
+        GrabFileResult::AccessForbidden => {
+            CheckResult::Failure("Could not read the Gemfile: Access forbidden.".to_owned())
+        }
+        GrabFileResult::Error(_) => {
+            CheckResult::Failure("Could not read the Gemfile: Request failure.".to_owned())
+        }
+    }
+}
+
+pub(crate) struct Brakeman(pub(crate) RailsApp);
+
+#[async_trait]
+impl Check for Brakeman {
+    fn id(&self) -> &'static str {
+        "brakeman"
+    }
+
+    fn title(&self) -> &'static str {
+        "Brakeman"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::RailsProjects
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Run Brakeman in a workflow, or commit a `config/brakeman.ignore` file."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        vec![verify_brakeman(requests, &inputs, &self.0).await]
+    }
+}
+
+/// Whether any step of the workflow runs Brakeman, either in a script or through an action.
+fn runs_brakeman(workflow: &Workflow) -> bool {
+    workflow.jobs().into_iter().any(|(_, job)| {
+        let steps = job.get("steps").and_then(|s| s.as_sequence());
+        steps.into_iter().flatten().any(|step| {
+            step.get("run")
+                .and_then(|r| r.as_str())
+                .is_some_and(|r| r.contains("brakeman"))
+                || step
+                    .get("uses")
+                    .and_then(|u| u.as_str())
+                    .and_then(|u| u.split('@').next())
+                    .is_some_and(|a| a.to_lowercase().contains("brakeman"))
+        })
+    })
+}
+
+async fn verify_brakeman(
+    mut requests: RateThrottle,
+    inputs: &Inputs,
+    rails_app: &RailsApp,
+) -> CheckResult {
+    let oc = octocrab_with_token_for(inputs);
+    match rails_app.get(&mut requests, &oc, inputs).await {
+        Ok(true) => {}
+        Ok(false) => return CheckResult::Ignore,
+        Err(e) => return e,
+    }
+    let sem = requests.acquire().await;
+    let ignore_file = file_check(&oc, inputs, "config/brakeman.ignore").await;
+    drop(sem);
+    match ignore_file {
+        FileCheckResult::Found => {
+            return CheckResult::Pass("Found a `config/brakeman.ignore` file.".to_owned())
+        }
+        FileCheckResult::NotFound => {}
+        FileCheckResult::AccessDenied => {
+            return CheckResult::Failure(
+                "Could not check for a `config/brakeman.ignore` file: Access denied.".to_owned(),
+            )
+        }
+        FileCheckResult::AccessForbidden => {
+            return CheckResult::Failure(
+                "Could not check for a `config/brakeman.ignore` file: Access forbidden.".to_owned(),
+            )
+        }
+        FileCheckResult::Error(_) => {
+            return CheckResult::Failure(
+                "Could not check for a `config/brakeman.ignore` file: Request failure.".to_owned(),
+            )
+        }
+    }
+    match grab_repo_workflows(&mut requests, &oc, inputs).await {
+        Ok(workflows) => match workflows.iter().find(|w| runs_brakeman(w)) {
+            Some(w) => CheckResult::Pass(format!("Brakeman runs in `{}`.", w.path)),
+            None => CheckResult::Failure(
+                "Brakeman does not run in any workflow, and there is no `config/brakeman.ignore` file."
+                    .to_owned(),
+            ),
+        },
+        Err(reason) => CheckResult::Failure(format!(
+            "Could not read workflows to check for Brakeman: {}",
+            reason
+        )),
+    }
+}
+
+pub(crate) struct Rubocop(pub(crate) RailsApp);
+
+#[async_trait]
+impl Check for Rubocop {
+    fn id(&self) -> &'static str {
+        "rubocop"
+    }
+
+    fn title(&self) -> &'static str {
+        "RuboCop"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::RailsProjects
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add a `.rubocop.yml` file."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        vec![verify_rubocop(requests, &inputs, &self.0).await]
+    }
+}
+
+static RUBOCOP_PATHS: [&str; 2] = [".rubocop.yml", ".rubocop.yaml"];
+
+async fn verify_rubocop(
+    mut requests: RateThrottle,
+    inputs: &Inputs,
+    rails_app: &RailsApp,
+) -> CheckResult {
+    let oc = octocrab_with_token_for(inputs);
+    match rails_app.get(&mut requests, &oc, inputs).await {
+        Ok(true) => {}
+        Ok(false) => return CheckResult::Ignore,
+        Err(e) => return e,
+    }
+    let _permit = requests.acquire().await;
+    let (file, path) = first_file_check(&oc, inputs, &RUBOCOP_PATHS).await;
+    match file {
+        FileCheckResult::Found => CheckResult::Pass(format!("Found a `{}` file.", path)),
+        FileCheckResult::AccessDenied => {
+            CheckResult::Failure("Could not find a `.rubocop.yml` file: Access denied.".to_owned())
+        }
+        FileCheckResult::AccessForbidden => CheckResult::Failure(
+            "Could not find a `.rubocop.yml` file: Access forbidden.".to_owned(),
+        ),
+        FileCheckResult::NotFound => {
+            CheckResult::Failure("Could not find a `.rubocop.yml` file.".to_owned())
+        }
+        FileCheckResult::Error(_) => CheckResult::Failure(
+            "Could not find a `.rubocop.yml` file: Request failure.".to_owned(),
+        ),
+    }
+}
+
+pub(crate) struct RailsSecrets(pub(crate) RailsApp);
+
+#[async_trait]
+impl Check for RailsSecrets {
+    fn id(&self) -> &'static str {
+        "rails_secrets"
+    }
+
+    fn title(&self) -> &'static str {
+        "Rails Secrets Not Committed"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::RailsProjects
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Remove the files from the repository, add them to `.gitignore`, and rotate the secrets they contained."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_rails_secrets(requests, &inputs, &self.0).await
+    }
+}
+
+/// Files holding the keys to Rails credentials, or environment variables.
+fn secret_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    path == "config/master.key"
+        || (path.starts_with("config/credentials/") && name.ends_with(".key"))
+        || name == ".env"
+}
+
+async fn verify_rails_secrets(
+    mut requests: RateThrottle,
+    inputs: &Inputs,
+    rails_app: &RailsApp,
+) -> Vec<CheckResult> {
+    let oc = octocrab_with_token_for(inputs);
+    match rails_app.get(&mut requests, &oc, inputs).await {
+        Ok(true) => {}
+        Ok(false) => return vec![CheckResult::Ignore],
+        Err(e) => return vec![e],
+    }
+    let _permit = requests.acquire().await;
+    let paths = match list_tree(&oc, inputs).await {
+        Ok(p) => p,
+        Err(reason) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not list repository files to check for committed secrets: {}",
+                reason
+            ))]
+        }
+    };
+    let committed: Vec<CheckResult> = paths
+        .iter()
+        .filter(|p| secret_file(p))
+        .map(|p| {
+            CheckResult::Failure(format!("`{}` is committed to the repository.", p))
+                .at(Location::file(p.clone()))
+        })
+        .collect();
+    if committed.is_empty() {
+        vec![CheckResult::Pass(
+            "No `config/master.key` or `.env` files are committed.".to_owned(),
+        )]
+    } else {
+        committed
+    }
+}
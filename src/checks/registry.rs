@@ -0,0 +1,159 @@
+use std::{sync::Arc, time::Instant};
+
+use chrono::Utc;
+use tokio::task::JoinSet;
+
+use crate::{
+    checks::{
+        actions_settings::WorkflowPermissions,
+        branch_protection::{DefaultBranchProtected, RequiredStatusChecksExist},
+        code_scanning::CodeScanning,
+        codeowners::CodeOwners,
+        copilot::CopilotIgnore,
+        dependabot::{
+            DependabotConfig, DependabotCoverage, DependabotEnabled, DependabotPolicy,
+            DependabotSecurityUpdates,
+        },
+        dependabot_alerts::DependabotAlerts,
+        quality::{RequiredFiles, UpdatesYellr},
+        rails_projects::{Brakeman, BundlerAudit, RailsApp, RailsSecrets, Rubocop, RubyVersion},
+        secret_scanning::SecretScanning,
+        security_policy::SecurityPolicy,
+        workflow_hardening::WorkflowHardening,
+        Check,
+    },
+    github_utils::RateThrottle,
+    inputs::Inputs,
+    results::CheckReport,
+    waivers::apply_waivers,
+};
+
+pub(crate) fn registered_checks() -> Vec<Arc<dyn Check>> {
+    // The Rails checks share the work of deciding whether this is a Rails app.
+    let rails_app = RailsApp::default();
+    vec![
+        Arc::new(DependabotEnabled),
+        Arc::new(DependabotConfig),
+        Arc::new(DependabotCoverage),
+        Arc::new(DependabotSecurityUpdates),
+        Arc::new(DependabotPolicy),
+        Arc::new(DependabotAlerts),
+        Arc::new(SecretScanning),
+        Arc::new(CodeScanning),
+        Arc::new(SecurityPolicy),
+        Arc::new(UpdatesYellr),
+        Arc::new(CopilotIgnore),
+        Arc::new(BundlerAudit),
+        Arc::new(RubyVersion(rails_app.clone())),
+        Arc::new(Brakeman(rails_app.clone())),
+        Arc::new(Rubocop(rails_app.clone())),
+        Arc::new(RailsSecrets(rails_app)),
+        Arc::new(DefaultBranchProtected),
+        Arc::new(RequiredStatusChecksExist),
+        Arc::new(RequiredFiles),
+        Arc::new(CodeOwners),
+        Arc::new(WorkflowHardening),
+        Arc::new(WorkflowPermissions),
+    ]
+}
+
+pub(crate) fn check_enabled(check: &dyn Check, inputs: &Inputs) -> bool {
+    let id = check.id();
+    if inputs.disable_checks.iter().any(|c| c == id) {
+        return false;
+    }
+    if inputs.enable_checks.iter().any(|c| c == id) {
+        return true;
+    }
+    inputs
+        .config
+        .enabled(id)
+        .unwrap_or_else(|| check.default_enabled())
+}
+
+pub(crate) fn enabled_checks(inputs: &Inputs) -> Vec<Arc<dyn Check>> {
+    registered_checks()
+        .into_iter()
+        .filter(|c| check_enabled(c.as_ref(), inputs))
+        .collect()
+}
+
+/// Runs every enabled check concurrently, applying configured severities and waivers.
+pub(crate) async fn run_checks(requests: RateThrottle, inputs: &Inputs) -> Vec<CheckReport> {
+    run_checks_matching(requests, inputs, |_| true).await
+}
+
+/// Runs the enabled checks accepted by `filter`, as `run_checks` does.
+pub(crate) async fn run_checks_matching(
+    requests: RateThrottle,
+    inputs: &Inputs,
+    filter: impl Fn(&dyn Check) -> bool,
+) -> Vec<CheckReport> {
+    let mut set = JoinSet::new();
+
+    for (index, check) in enabled_checks(inputs)
+        .into_iter()
+        .filter(|c| filter(c.as_ref()))
+        .enumerate()
+    {
+        let requests = requests.clone();
+        let inputs = inputs.clone();
+        set.spawn(async move {
+            let started = Instant::now();
+            let results = check.run(requests, inputs).await;
+            let report = CheckReport {
+                id: check.id(),
+                title: check.title(),
+                category: check.category(),
+                location: check.location(),
+                remediation: check.remediation(),
+                duration: started.elapsed(),
+                results,
+            };
+            (index, report)
+        });
+    }
+
+    // Tasks finish in any order, but reports are given in registration order.
+    let mut finished = set.join_all().await;
+    finished.sort_by_key(|(index, _)| *index);
+    let today = Utc::now().date_naive();
+    let mut reports = Vec::new();
+    for (_, mut report) in finished {
+        if let Some(s) = inputs.config.severity(report.id) {
+            report.results = report
+                .results
+                .into_iter()
+                .map(|r| r.with_severity(s))
+                .collect();
+        }
+        report.results = apply_waivers(&inputs.config, report.id, today, report.results);
+        reports.push(report);
+    }
+    reports
+}
+
+pub(crate) fn list_checks() -> String {
+    let checks = registered_checks();
+    let id_width = checks.iter().map(|c| c.id().len()).max().unwrap_or(0);
+    let category_width = checks
+        .iter()
+        .map(|c| c.category().to_string().len())
+        .max()
+        .unwrap_or(0);
+    let mut listing = String::new();
+    for check in checks {
+        listing.push_str(&format!(
+            "{:id_width$}  {:category_width$}  {:8}  {}\n",
+            check.id(),
+            check.category().to_string(),
+            if check.default_enabled() {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            check.title(),
+        ));
+    }
+    listing
+}
@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use octocrab::Page;
+use serde::Deserialize;
+
+use crate::{
+    checks::{Check, CheckCategory},
+    github_utils::{octocrab_with_access_token_for, request_failure_reason, RateThrottle},
+    inputs::Inputs,
+    results::CheckResult,
+};
+
+pub(crate) struct SecretScanning;
+
+#[async_trait]
+impl Check for SecretScanning {
+    fn id(&self) -> &'static str {
+        "secret_scanning"
+    }
+
+    fn title(&self) -> &'static str {
+        "Secret Scanning and Push Protection"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::SecretScanning
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Enable secret scanning and push protection under the repository's Code security settings, and resolve any open secret scanning alerts."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn reads_commit(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_secret_scanning(requests, inputs).await
+    }
+}
+
+#[derive(Deserialize)]
+struct SecurityRepository {
+    #[serde(default)]
+    private: bool,
+    security_and_analysis: Option<SecurityAndAnalysis>,
+}
+
+#[derive(Deserialize)]
+struct SecurityAndAnalysis {
+    advanced_security: Option<FeatureStatus>,
+    secret_scanning: Option<FeatureStatus>,
+    secret_scanning_push_protection: Option<FeatureStatus>,
+}
+
+#[derive(Deserialize)]
+struct FeatureStatus {
+    status: String,
+}
+
+fn feature_enabled(feature: &Option<FeatureStatus>) -> bool {
+    feature.as_ref().is_some_and(|f| f.status == "enabled")
+}
+
+#[derive(Deserialize)]
+struct SecretScanningAlert {
+    number: u64,
+    html_url: String,
+    secret_type_display_name: Option<String>,
+}
+
+fn secret_type(alert: &SecretScanningAlert) -> &str {
+    alert
+        .secret_type_display_name
+        .as_deref()
+        .unwrap_or("unknown secret type")
+}
+
+fn open_alerts_table(alerts: &[SecretScanningAlert]) -> String {
+    let mut md = String::from("| Secret Type | Alert |\n|-----|-----|\n");
+    for alert in alerts {
+        md.push_str(&format!(
+            "| {} | [#{}]({}) |\n",
+            secret_type(alert),
+            alert.number,
+            alert.html_url
+        ));
+    }
+    md
+}
+
+async fn verify_secret_scanning(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let oc = octocrab_with_access_token_for(&inputs);
+    let sem = requests.acquire().await;
+    let repo: SecurityRepository = match oc
+        .get(format!("/repos/{}", inputs.repository), None::<&()>)
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not check if secret scanning was enabled: {}",
+                request_failure_reason(&e)
+            ))]
+        }
+    };
+    drop(sem);
+
+    // The settings are only returned to administrators, and private repositories only have
+    // secret scanning with Advanced Security.
+    let settings = match repo.security_and_analysis {
+        Some(s) => s,
+        None => {
+            return vec![CheckResult::Warning(
+                "Could not check if secret scanning was enabled: security settings were not returned for the access token.".to_owned(),
+            )]
+        }
+    };
+    if repo.private && !feature_enabled(&settings.advanced_security) {
+        return vec![CheckResult::Ignore];
+    }
+
+    let mut results = Vec::new();
+    if feature_enabled(&settings.secret_scanning) {
+        results.push(CheckResult::Pass("Secret scanning is enabled".to_owned()));
+    } else {
+        results.push(CheckResult::Failure(
+            "Secret scanning is not enabled.".to_owned(),
+        ));
+    }
+    if feature_enabled(&settings.secret_scanning_push_protection) {
+        results.push(CheckResult::Pass(
+            "Secret scanning push protection is enabled".to_owned(),
+        ));
+    } else {
+        results.push(CheckResult::Failure(
+            "Secret scanning push protection is not enabled.".to_owned(),
+        ));
+    }
+
+    let fail_on_open_alerts: bool = inputs
+        .config
+        .option("secret_scanning", "fail_on_open_alerts")
+        .unwrap_or(false);
+    if fail_on_open_alerts && feature_enabled(&settings.secret_scanning) {
+        let _permit = requests.acquire().await;
+        let alerts = match oc
+            .get::<Page<SecretScanningAlert>, _, _>(
+                format!(
+                    "/repos/{}/secret-scanning/alerts?state=open&per_page=100",
+                    inputs.repository
+                ),
+                None::<&()>,
+            )
+            .await
+        {
+            Ok(page) => oc.all_pages(page).await,
+            Err(e) => Err(e),
+        };
+        match alerts {
+            Ok(a) if a.is_empty() => results.push(CheckResult::Pass(
+                "No open secret scanning alerts".to_owned(),
+            )),
+            Ok(a) => {
+                results.extend(a.iter().map(|alert| {
+                    CheckResult::Failure(format!(
+                        "Secret scanning alert #{} is open: {}.",
+                        alert.number,
+                        secret_type(alert)
+                    ))
+                }));
+                results.push(CheckResult::Details(open_alerts_table(&a)));
+            }
+            Err(e) => results.push(CheckResult::Failure(format!(
+                "Could not list open secret scanning alerts: {}",
+                request_failure_reason(&e)
+            ))),
+        }
+    }
+    results
+}
@@ -0,0 +1,147 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::{
+    checks::{Check, CheckCategory},
+    github_utils::{
+        first_file_check, first_file_check_in, octocrab_with_access_token_for,
+        octocrab_with_token_for, request_failure_reason, FileCheckResult, RateThrottle,
+    },
+    inputs::Inputs,
+    results::{CheckResult, Location},
+};
+
+pub(crate) struct SecurityPolicy;
+
+#[async_trait]
+impl Check for SecurityPolicy {
+    fn id(&self) -> &'static str {
+        "security_policy"
+    }
+
+    fn title(&self) -> &'static str {
+        "Security Policy"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::SecurityPolicy
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Add a `SECURITY.md` describing how to report vulnerabilities, to the repository or the organization's `.github` repository, and enable private vulnerability reporting."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_security_policy(requests, inputs).await
+    }
+}
+
+static SECURITY_POLICY_PATHS: [&str; 3] =
+    ["SECURITY.md", ".github/SECURITY.md", "docs/SECURITY.md"];
+
+// GitHub falls back to the owner's public `.github` repository for community health files.
+static COMMUNITY_HEALTH_REPOSITORY: &str = ".github";
+
+#[derive(Deserialize)]
+struct PrivateVulnerabilityReporting {
+    enabled: bool,
+}
+
+async fn verify_security_policy(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let oc = octocrab_with_token_for(&inputs);
+    let sem = requests.acquire().await;
+    let (file, path) = first_file_check(&oc, &inputs, &SECURITY_POLICY_PATHS).await;
+    drop(sem);
+    let mut results = match file {
+        FileCheckResult::Found => {
+            vec![
+                CheckResult::Pass(format!("Found a `{}` security policy", path))
+                    .at(Location::file(path)),
+            ]
+        }
+        FileCheckResult::NotFound => {
+            // The default branch of the `.github` repository is what GitHub shows.
+            let access_oc = octocrab_with_access_token_for(&inputs);
+            let _permit = requests.acquire().await;
+            let (inherited, path) = first_file_check_in(
+                &access_oc,
+                &inputs.repository_owner,
+                COMMUNITY_HEALTH_REPOSITORY,
+                "HEAD",
+                &SECURITY_POLICY_PATHS,
+            )
+            .await;
+            match inherited {
+                FileCheckResult::Found => vec![CheckResult::Pass(format!(
+                    "Inherited the `{}` security policy from `{}/{}`",
+                    path, inputs.repository_owner, COMMUNITY_HEALTH_REPOSITORY
+                ))],
+                FileCheckResult::NotFound => vec![CheckResult::Failure(format!(
+                    "Could not find a SECURITY.md in the root, `.github/` or `docs/`, or in `{}/{}`.",
+                    inputs.repository_owner, COMMUNITY_HEALTH_REPOSITORY
+                ))],
+                FileCheckResult::AccessDenied => vec![CheckResult::Failure(format!(
+                    "Could not check `{}/{}` for an inherited SECURITY.md: Access denied.",
+                    inputs.repository_owner, COMMUNITY_HEALTH_REPOSITORY
+                ))],
+                FileCheckResult::AccessForbidden => vec![CheckResult::Failure(format!(
+                    "Could not check `{}/{}` for an inherited SECURITY.md: Access forbidden.",
+                    inputs.repository_owner, COMMUNITY_HEALTH_REPOSITORY
+                ))],
+                FileCheckResult::Error(e) => vec![CheckResult::Failure(format!(
+                    "Could not check `{}/{}` for an inherited SECURITY.md: {}",
+                    inputs.repository_owner,
+                    COMMUNITY_HEALTH_REPOSITORY,
+                    request_failure_reason(&e)
+                ))],
+            }
+        }
+        FileCheckResult::AccessDenied => vec![CheckResult::Failure(format!(
+            "Could not find a `{}` file: Access denied.",
+            path
+        ))],
+        FileCheckResult::AccessForbidden => vec![CheckResult::Failure(format!(
+            "Could not find a `{}` file: Access forbidden.",
+            path
+        ))],
+        FileCheckResult::Error(_) => vec![CheckResult::Failure(format!(
+            "Could not find a `{}` file.",
+            path
+        ))],
+    };
+
+    let require_private_reporting: bool = inputs
+        .config
+        .option("security_policy", "require_private_vulnerability_reporting")
+        .unwrap_or(false);
+    if require_private_reporting {
+        let access_oc = octocrab_with_access_token_for(&inputs);
+        let _permit = requests.acquire().await;
+        let reporting: Result<PrivateVulnerabilityReporting, _> = access_oc
+            .get(
+                format!(
+                    "/repos/{}/private-vulnerability-reporting",
+                    inputs.repository
+                ),
+                None::<&()>,
+            )
+            .await;
+        results.push(match reporting {
+            Ok(r) if r.enabled => {
+                CheckResult::Pass("Private vulnerability reporting is enabled".to_owned())
+            }
+            Ok(_) => {
+                CheckResult::Failure("Private vulnerability reporting is not enabled.".to_owned())
+            }
+            Err(e) => CheckResult::Failure(format!(
+                "Could not check if private vulnerability reporting was enabled: {}",
+                request_failure_reason(&e)
+            )),
+        });
+    }
+    results
+}
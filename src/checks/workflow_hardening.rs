@@ -0,0 +1,279 @@
+use async_trait::async_trait;
+use serde_yaml::Value;
+
+use crate::{
+    checks::{Check, CheckCategory},
+    github_utils::{octocrab_with_token_for, RateThrottle},
+    inputs::Inputs,
+    results::{CheckResult, Location},
+    workflow_utils::{grab_repo_workflows, uses_action, Workflow},
+    yaml_utils::{key_line_between, mapping_key_lines, top_level_key_line},
+};
+
+pub(crate) struct WorkflowHardening;
+
+#[async_trait]
+impl Check for WorkflowHardening {
+    fn id(&self) -> &'static str {
+        "workflow_hardening"
+    }
+
+    fn title(&self) -> &'static str {
+        "Workflow Hardening"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Workflows
+    }
+
+    fn remediation(&self) -> &'static str {
+        "Set least-privilege `permissions:`, pin third-party actions to a full commit SHA, never check out pull request code in `pull_request_target` workflows, and pass event data to `run:` scripts through `env:`."
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    async fn run(&self, requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+        verify_workflow_hardening(requests, inputs).await
+    }
+}
+
+static DEFAULT_TRUSTED_OWNERS: [&str; 2] = ["actions", "github"];
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// The value of a `key: value` line, which may be a sequence item, without quotes or comments.
+fn line_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let trimmed = line.trim_start().trim_start_matches("- ").trim_start();
+    let value = trimmed.strip_prefix(key)?.strip_prefix(':')?;
+    let value = value.split_once(" #").map(|(v, _)| v).unwrap_or(value);
+    Some(value.trim().trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// Each job with the first and last line of its definition.
+fn job_ranges(workflow: &Workflow) -> Vec<(&str, &Value, usize, usize)> {
+    let jobs_line = top_level_key_line(&workflow.text, "jobs").unwrap_or(1);
+    let total = workflow.text.lines().count();
+    let lines = mapping_key_lines(&workflow.text, "jobs");
+    workflow
+        .jobs()
+        .into_iter()
+        .map(|(id, job)| match lines.iter().find(|(k, _, _)| *k == id) {
+            Some((_, start, end)) => (id, job, *start, *end),
+            None => (id, job, jobs_line, total),
+        })
+        .collect()
+}
+
+fn is_write_all(permissions: Option<&Value>) -> bool {
+    permissions.and_then(|p| p.as_str()) == Some("write-all")
+}
+
+fn permission_findings(workflow: &Workflow) -> Vec<CheckResult> {
+    let mut findings = Vec::new();
+    let top = workflow.value.get("permissions");
+    if is_write_all(top) {
+        let line = top_level_key_line(&workflow.text, "permissions").unwrap_or(1);
+        findings.push(
+            CheckResult::Failure(
+                "`permissions: write-all` gives the workflow token every write permission."
+                    .to_owned(),
+            )
+            .at(Location::line(workflow.path.clone(), line)),
+        );
+    }
+    let jobs = job_ranges(workflow);
+    if top.is_none() {
+        let unrestricted: Vec<&str> = jobs
+            .iter()
+            .filter(|(_, job, _, _)| job.get("permissions").is_none())
+            .map(|(id, _, _, _)| *id)
+            .collect();
+        if !unrestricted.is_empty() {
+            findings.push(
+                CheckResult::Failure(format!(
+                    "No top-level `permissions:`, so jobs `{}` get the default token permissions.",
+                    unrestricted.join("`, `")
+                ))
+                .at(Location::line(workflow.path.clone(), 1)),
+            );
+        }
+    }
+    for (id, job, start, end) in jobs {
+        if is_write_all(job.get("permissions")) {
+            let line = key_line_between(&workflow.text, "permissions", start, end).unwrap_or(start);
+            findings.push(
+                CheckResult::Failure(format!(
+                    "Job `{}` sets `permissions: write-all`, giving its token every write permission.",
+                    id
+                ))
+                .at(Location::line(workflow.path.clone(), line)),
+            );
+        }
+    }
+    findings
+}
+
+fn pinned_to_sha(reference: &str) -> bool {
+    reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn pinning_findings(workflow: &Workflow, trusted_owners: &[String]) -> Vec<CheckResult> {
+    let mut findings = Vec::new();
+    for (i, line) in workflow.text.lines().enumerate() {
+        let uses = match line_value(line, "uses") {
+            Some(u) if !u.is_empty() => u,
+            _ => continue,
+        };
+        if uses.starts_with("./") || uses.starts_with("docker://") {
+            continue;
+        }
+        let (action, reference) = uses.split_once('@').unwrap_or((uses, ""));
+        let owner = action.split('/').next().unwrap_or_default();
+        if trusted_owners.iter().any(|t| t == owner) || pinned_to_sha(reference) {
+            continue;
+        }
+        findings.push(
+            CheckResult::Failure(format!("`{}` is not pinned to a full commit SHA.", uses))
+                .at(Location::line(workflow.path.clone(), i + 1)),
+        );
+    }
+    findings
+}
+
+fn triggered_by(workflow: &Workflow, event: &str) -> bool {
+    match workflow.value.get("on") {
+        Some(Value::String(s)) => s == event,
+        Some(Value::Sequence(events)) => events.iter().any(|e| e.as_str() == Some(event)),
+        Some(Value::Mapping(events)) => events.contains_key(event),
+        _ => false,
+    }
+}
+
+fn checks_out_head(reference: &str) -> bool {
+    reference.contains("github.event.pull_request.head") || reference.contains("github.head_ref")
+}
+
+fn pull_request_target_findings(workflow: &Workflow) -> Vec<CheckResult> {
+    if !triggered_by(workflow, "pull_request_target") {
+        return Vec::new();
+    }
+    let mut findings = Vec::new();
+    for (id, job, start, end) in job_ranges(workflow) {
+        let steps = job.get("steps").and_then(|s| s.as_sequence());
+        let checkouts = steps
+            .into_iter()
+            .flatten()
+            .filter(|s| uses_action(s, "actions/checkout"));
+        for step in checkouts {
+            let reference = match step
+                .get("with")
+                .and_then(|w| w.get("ref"))
+                .and_then(|r| r.as_str())
+            {
+                Some(r) if checks_out_head(r) => r,
+                _ => continue,
+            };
+            let line = workflow
+                .text
+                .lines()
+                .enumerate()
+                .skip(start.saturating_sub(1))
+                .take(end + 1 - start)
+                .find(|(_, l)| line_value(l, "ref") == Some(reference))
+                .map(|(i, _)| i + 1)
+                .unwrap_or(start);
+            findings.push(
+                CheckResult::Failure(format!(
+                    "Job `{}` checks out the pull request head (`{}`) in a `pull_request_target` workflow, running untrusted code with a privileged token.",
+                    id, reference
+                ))
+                .at(Location::line(workflow.path.clone(), line)),
+            );
+        }
+    }
+    findings
+}
+
+fn interpolates_event(text: &str) -> bool {
+    text.split("${{")
+        .skip(1)
+        .any(|e| e.trim_start().starts_with("github.event."))
+}
+
+fn run_script_findings(workflow: &Workflow) -> Vec<CheckResult> {
+    let lines: Vec<&str> = workflow.text.lines().collect();
+    let mut findings = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let inline = match line_value(line, "run") {
+            Some(v) => v,
+            None => {
+                i += 1;
+                continue;
+            }
+        };
+        let key_indent = line.find("run:").unwrap_or_else(|| indentation(line));
+        let mut script = vec![(i, inline)];
+        i += 1;
+        if inline.starts_with('|') || inline.starts_with('>') {
+            while i < lines.len()
+                && (lines[i].trim().is_empty() || indentation(lines[i]) > key_indent)
+            {
+                script.push((i, lines[i]));
+                i += 1;
+            }
+        }
+        for (n, text) in script {
+            if interpolates_event(text) {
+                findings.push(
+                    CheckResult::Failure(
+                        "`${{ github.event.* }}` is interpolated directly into a `run:` script; pass it through `env:` instead.".to_owned(),
+                    )
+                    .at(Location::line(workflow.path.clone(), n + 1)),
+                );
+            }
+        }
+    }
+    findings
+}
+
+async fn verify_workflow_hardening(mut requests: RateThrottle, inputs: Inputs) -> Vec<CheckResult> {
+    let trusted_owners: Vec<String> = inputs
+        .config
+        .option("workflow_hardening", "trusted_owners")
+        .unwrap_or_else(|| DEFAULT_TRUSTED_OWNERS.map(|o| o.to_owned()).to_vec());
+    let oc = octocrab_with_token_for(&inputs);
+    let workflows = match grab_repo_workflows(&mut requests, &oc, &inputs).await {
+        Ok(w) => w,
+        Err(reason) => {
+            return vec![CheckResult::Failure(format!(
+                "Could not read workflows to audit: {}",
+                reason
+            ))]
+        }
+    };
+    if workflows.is_empty() {
+        return vec![CheckResult::Ignore];
+    }
+
+    let mut results = Vec::new();
+    for workflow in workflows.iter() {
+        results.extend(permission_findings(workflow));
+        results.extend(pinning_findings(workflow, &trusted_owners));
+        results.extend(pull_request_target_findings(workflow));
+        results.extend(run_script_findings(workflow));
+    }
+    if results.is_empty() {
+        vec![CheckResult::Pass(format!(
+            "{} workflows passed the hardening audit",
+            workflows.len()
+        ))]
+    } else {
+        results
+    }
+}
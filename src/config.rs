@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use github_actions::issue_command;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    github_utils::{grab_file, octocrab_with_token_for, GrabFileResult, RateThrottle},
+    inputs::Inputs,
+    results::Severity,
+};
+
+pub(crate) static CONFIG_FILE_PATH: &str = ".github/ic-repo-checks.yml";
+
+/// Repository level configuration, read from `.github/ic-repo-checks.yml`.
+#[derive(Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RepoConfig {
+    #[serde(default)]
+    pub(crate) checks: HashMap<String, CheckConfig>,
+    #[serde(default)]
+    pub(crate) waivers: Vec<Waiver>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct CheckConfig {
+    pub(crate) enabled: Option<bool>,
+    pub(crate) severity: Option<Severity>,
+    #[serde(default)]
+    pub(crate) options: serde_yaml::Mapping,
+}
+
+/// An exemption from a failing check, valid up to and including `expires`.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Waiver {
+    pub(crate) check: String,
+    pub(crate) reason: String,
+    pub(crate) expires: NaiveDate,
+}
+
+impl RepoConfig {
+    pub(crate) fn enabled(&self, check_id: &str) -> Option<bool> {
+        self.checks.get(check_id).and_then(|c| c.enabled)
+    }
+
+    pub(crate) fn severity(&self, check_id: &str) -> Option<Severity> {
+        self.checks.get(check_id).and_then(|c| c.severity)
+    }
+
+    /// Reads a per-check option, warning and falling back to `None` if it has the wrong shape.
+    pub(crate) fn option<T: DeserializeOwned>(&self, check_id: &str, key: &str) -> Option<T> {
+        let value = self.checks.get(check_id)?.options.get(key)?;
+        match serde_yaml::from_value(value.clone()) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                github_actions::warn!(format!(
+                    "Ignoring invalid option `{}` for check `{}` in {}: {}",
+                    key, check_id, CONFIG_FILE_PATH, e
+                ));
+                None
+            }
+        }
+    }
+}
+
+pub(crate) async fn load_config(
+    mut requests: RateThrottle,
+    inputs: &Inputs,
+) -> Result<RepoConfig, String> {
+    let oc = octocrab_with_token_for(inputs);
+    let _permit = requests.acquire().await;
+    match grab_file(&oc, inputs, CONFIG_FILE_PATH).await {
+        GrabFileResult::File(contents) => serde_yaml::from_slice(&contents)
+            .map_err(|e| format!("Could not parse `{}`: {}", CONFIG_FILE_PATH, e)),
+        GrabFileResult::NotFound => Ok(RepoConfig::default()),
+        GrabFileResult::AccessDenied => Err(format!(
+            "Could not read `{}`: Access denied.",
+            CONFIG_FILE_PATH
+        )),
+        GrabFileResult::AccessForbidden => Err(format!(
+            "Could not read `{}`: Access forbidden.",
+            CONFIG_FILE_PATH
+        )),
+        GrabFileResult::Error(_) => Err(format!(
+            "Could not read `{}`: Request failure.",
+            CONFIG_FILE_PATH
+        )),
+    }
+}
@@ -2,6 +2,7 @@ use std::{sync::Arc, time::Duration};
 
 use http_body_util::BodyExt;
 use octocrab::{repos::RepoHandler, Error, Octocrab, OctocrabBuilder};
+use serde::Deserialize;
 use tokio::sync::{Semaphore, SemaphorePermit};
 
 use crate::inputs::Inputs;
@@ -39,7 +40,7 @@ impl RateThrottle {
         }
     }
 
-    pub(crate) async fn acquire(&mut self) -> SemaphorePermit {
+    pub(crate) async fn acquire(&mut self) -> SemaphorePermit<'_> {
         let borrow = self.inner.acquire().await;
         tokio::time::sleep(Duration::from_millis(100)).await;
         borrow.unwrap()
@@ -56,17 +57,38 @@ pub(crate) fn octocrab_with_access_token_for(inputs: &Inputs) -> Octocrab {
     ob.build().unwrap()
 }
 
-pub(crate) fn octocrab_repo_handler_for<'a>(oc: &'a Octocrab, inputs: &Inputs) -> RepoHandler<'a> {
-    let repo_name = inputs
+pub(crate) fn repo_name_for(inputs: &Inputs) -> &str {
+    inputs
         .repository
         .strip_prefix((inputs.repository_owner.clone() + "/").as_str())
-        .unwrap();
-    oc.repos(inputs.repository_owner.clone(), repo_name)
+        .unwrap()
+}
+
+pub(crate) fn octocrab_repo_handler_for<'a>(oc: &'a Octocrab, inputs: &Inputs) -> RepoHandler<'a> {
+    oc.repos(inputs.repository_owner.clone(), repo_name_for(inputs))
 }
 
 pub(crate) async fn file_check(oc: &Octocrab, inputs: &Inputs, file_path: &str) -> FileCheckResult {
-    let rh = octocrab_repo_handler_for(oc, inputs);
-    let dependabot_file = rh.raw_file(inputs.sha.clone(), file_path).await;
+    file_check_in(
+        oc,
+        &inputs.repository_owner,
+        repo_name_for(inputs),
+        &inputs.sha,
+        file_path,
+    )
+    .await
+}
+
+/// Like [`file_check`], but for a file in any repository at the given ref.
+pub(crate) async fn file_check_in(
+    oc: &Octocrab,
+    owner: &str,
+    repo: &str,
+    reference: &str,
+    file_path: &str,
+) -> FileCheckResult {
+    let rh = oc.repos(owner, repo);
+    let dependabot_file = rh.raw_file(reference.to_owned(), file_path).await;
     match dependabot_file {
         Ok(x) => {
             // TODO: 401 is unauthorized
@@ -103,3 +125,104 @@ pub(crate) async fn grab_file(oc: &Octocrab, inputs: &Inputs, file_path: &str) -
         Err(e) => GrabFileResult::Error(e),
     }
 }
+
+#[derive(Deserialize)]
+struct GitTree {
+    tree: Vec<GitTreeEntry>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Deserialize)]
+struct GitTreeEntry {
+    path: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+}
+
+/// Why an API request failed, phrased to follow "Could not ...: ".
+pub(crate) fn request_failure_reason(e: &Error) -> &'static str {
+    match e {
+        Error::GitHub { source, .. } if source.status_code.as_u16() == 401 => "Access denied.",
+        Error::GitHub { source, .. } if source.status_code.as_u16() == 403 => "Access forbidden.",
+        Error::GitHub { source, .. } if source.status_code.as_u16() == 404 => "Not found.",
+        _ => "Request failure.",
+    }
+}
+
+/// Lists the path of every file in the repository at `inputs.sha`.
+///
+/// Fails rather than returning part of the tree when it is too large for GitHub to list at once,
+/// since checks would otherwise pass on the files they never saw.
+pub(crate) async fn list_tree(oc: &Octocrab, inputs: &Inputs) -> Result<Vec<String>, &'static str> {
+    let tree: GitTree = oc
+        .get(
+            format!(
+                "/repos/{}/git/trees/{}?recursive=1",
+                inputs.repository, inputs.sha
+            ),
+            None::<&()>,
+        )
+        .await
+        .map_err(|e| request_failure_reason(&e))?;
+    if tree.truncated {
+        return Err("The repository has too many files for GitHub to list.");
+    }
+    Ok(tree
+        .tree
+        .into_iter()
+        .filter(|e| e.entry_type == "blob")
+        .map(|e| e.path)
+        .collect())
+}
+
+/// Checks each candidate path in turn, returning the first which was found along with its path.
+///
+/// Stops early on anything other than a missing file, since the remaining candidates can't be
+/// checked either.
+pub(crate) async fn first_file_check<'a>(
+    oc: &Octocrab,
+    inputs: &Inputs,
+    candidates: &[&'a str],
+) -> (FileCheckResult, &'a str) {
+    first_file_check_in(
+        oc,
+        &inputs.repository_owner,
+        repo_name_for(inputs),
+        &inputs.sha,
+        candidates,
+    )
+    .await
+}
+
+/// Like [`first_file_check`], but for files in any repository at the given ref.
+pub(crate) async fn first_file_check_in<'a>(
+    oc: &Octocrab,
+    owner: &str,
+    repo: &str,
+    reference: &str,
+    candidates: &[&'a str],
+) -> (FileCheckResult, &'a str) {
+    for candidate in candidates {
+        match file_check_in(oc, owner, repo, reference, candidate).await {
+            FileCheckResult::NotFound => continue,
+            other => return (other, candidate),
+        }
+    }
+    (FileCheckResult::NotFound, candidates[0])
+}
+
+/// Like [`first_file_check`], but returns the contents of the first candidate found.
+pub(crate) async fn grab_first_file<'a>(
+    oc: &Octocrab,
+    inputs: &Inputs,
+    candidates: &[&'a str],
+) -> (GrabFileResult, &'a str) {
+    for candidate in candidates {
+        match grab_file(oc, inputs, candidate).await {
+            GrabFileResult::NotFound => continue,
+            other => return (other, candidate),
+        }
+    }
+    (GrabFileResult::NotFound, candidates[0])
+}
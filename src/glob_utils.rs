@@ -0,0 +1,36 @@
+/// Matches the segments of a path against the segments of a glob pattern.  `*` matches any run of
+/// characters within a single segment, and a `**` segment matches any number of segments.
+pub(crate) fn glob_matches(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            glob_matches(&pattern[1..], path)
+                || (!path.is_empty() && glob_matches(pattern, &path[1..]))
+        }
+        (Some(p), Some(d)) => segment_matches(p, d) && glob_matches(&pattern[1..], &path[1..]),
+        _ => false,
+    }
+}
+
+/// Matches one path segment against a pattern segment, where each `*` matches any run of
+/// characters.
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = segment.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*` at all, so the segment has to be the pattern itself.
+        return rest.is_empty();
+    };
+    // Taking the earliest place each middle piece fits leaves the most room for the rest.
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
 use github_actions::{
-    BoolInputResult, InputResult, GITHUB_REPOSITORY, GITHUB_REPOSITORY_OWNER, GITHUB_SHA,
-    GITHUB_STEP_SUMMARY,
+    BoolInputResult, InputResult, GITHUB_EVENT_NAME, GITHUB_EVENT_PATH, GITHUB_REPOSITORY,
+    GITHUB_REPOSITORY_OWNER, GITHUB_SHA, GITHUB_STEP_SUMMARY,
 };
 
+use crate::{config::RepoConfig, results::Severity};
+
 enum InputValue {
     Str(String),
     Boolean(bool),
@@ -20,7 +22,7 @@ impl InputValue {
 
     unsafe fn as_bool(&self) -> bool {
         match self {
-            Self::Boolean(b) => b.clone(),
+            Self::Boolean(b) => *b,
             Self::Str(_) => panic!("not a string value!"),
         }
     }
@@ -78,12 +80,31 @@ impl InputReader {
         }
     }
 
+    fn read_optional_str_input(&mut self, key: &str, default: &str) {
+        let r_val = github_actions::get_input(key);
+        match r_val {
+            Ok(x) => {
+                self.inputs_read
+                    .insert("INPUT_".to_owned() + key, InputValue::Str(x));
+            }
+            Err(InputResult::VarError(std::env::VarError::NotPresent)) => {
+                self.inputs_read.insert(
+                    "INPUT_".to_owned() + key,
+                    InputValue::Str(default.to_owned()),
+                );
+            }
+            Err(InputResult::VarError(std::env::VarError::NotUnicode(_))) => self
+                .failures
+                .push(format!("{} was not properly encoded as an input.", key)),
+        }
+    }
+
     fn read_bool_input(&mut self, key: &str) {
         let r_val = github_actions::get_bool_input(key);
         match r_val {
             Ok(x) => {
                 self.inputs_read
-                    .insert("INPUT_".to_owned() + key, InputValue::Boolean(x.clone()));
+                    .insert("INPUT_".to_owned() + key, InputValue::Boolean(x));
             }
             Err(y) => match y {
                 BoolInputResult::TypeError => self.failures.push(format!(
@@ -102,6 +123,20 @@ impl InputReader {
         }
     }
 
+    fn read_optional_bool_input(&mut self, key: &str, default: bool) {
+        match github_actions::get_input(key) {
+            Ok(x) if x.is_empty() => {
+                self.inputs_read
+                    .insert("INPUT_".to_owned() + key, InputValue::Boolean(default));
+            }
+            Err(InputResult::VarError(std::env::VarError::NotPresent)) => {
+                self.inputs_read
+                    .insert("INPUT_".to_owned() + key, InputValue::Boolean(default));
+            }
+            _ => self.read_bool_input(key),
+        }
+    }
+
     unsafe fn get_str_env(&self, key: &str) -> &str {
         self.inputs_read.get(key).unwrap().as_str()
     }
@@ -121,6 +156,27 @@ impl InputReader {
     }
 }
 
+/// The pull request which triggered the workflow, for `pull_request` events.
+#[derive(Clone)]
+pub(crate) struct PullRequestEvent {
+    pub(crate) number: u64,
+    pub(crate) head_sha: String,
+}
+
+fn read_pull_request_event() -> Option<PullRequestEvent> {
+    let event_name = std::env::var(GITHUB_EVENT_NAME).ok()?;
+    if event_name != "pull_request" && event_name != "pull_request_target" {
+        return None;
+    }
+    let event_path = std::env::var(GITHUB_EVENT_PATH).ok()?;
+    let event: serde_json::Value = serde_json::from_slice(&std::fs::read(event_path).ok()?).ok()?;
+    let pull_request = event.get("pull_request")?;
+    Some(PullRequestEvent {
+        number: pull_request.get("number")?.as_u64()?,
+        head_sha: pull_request.get("head")?.get("sha")?.as_str()?.to_owned(),
+    })
+}
+
 #[derive(Clone)]
 pub(crate) struct Inputs {
     pub(crate) repository_owner: String,
@@ -129,10 +185,16 @@ pub(crate) struct Inputs {
     pub(crate) sha: String,
     pub(crate) access_token: String,
     pub(crate) step_summary_path: String,
-    pub(crate) check_dependabot: bool,
-    pub(crate) check_yellr: bool,
-    pub(crate) check_bundler_audit: bool,
-    pub(crate) check_default_branch_protected: bool,
+    pub(crate) enable_checks: Vec<String>,
+    pub(crate) disable_checks: Vec<String>,
+    pub(crate) fail_on: Severity,
+    pub(crate) sarif_path: String,
+    pub(crate) json_report_path: String,
+    pub(crate) junit_path: String,
+    pub(crate) create_check_run: bool,
+    pub(crate) comment_on_pull_request: bool,
+    pub(crate) pull_request: Option<PullRequestEvent>,
+    pub(crate) config: RepoConfig,
 }
 
 static GITHUB_TOKEN: &str = "GITHUB_TOKEN";
@@ -141,6 +203,44 @@ static INPUT_CHECK_DEPENDABOT: &str = "CHECK_DEPENDABOT";
 static INPUT_CHECK_YELLR: &str = "CHECK_YELLR";
 static INPUT_CHECK_BUNDLER_AUDIT: &str = "CHECK_BUNDLER_AUDIT";
 static INPUT_CHECK_DEFAULT_BRANCH_PROTECTED: &str = "CHECK_DEFAULT_BRANCH_PROTECTED";
+static INPUT_ENABLE_CHECKS: &str = "ENABLE_CHECKS";
+static INPUT_DISABLE_CHECKS: &str = "DISABLE_CHECKS";
+static INPUT_FAIL_ON: &str = "FAIL_ON";
+static INPUT_SARIF_PATH: &str = "SARIF_PATH";
+static INPUT_JSON_REPORT_PATH: &str = "JSON_REPORT_PATH";
+static INPUT_JUNIT_PATH: &str = "JUNIT_PATH";
+static INPUT_CREATE_CHECK_RUN: &str = "CREATE_CHECK_RUN";
+static INPUT_COMMENT_ON_PULL_REQUEST: &str = "COMMENT_ON_PULL_REQUEST";
+
+// The original boolean inputs, and the check ids they switch off when set to false.
+static LEGACY_CHECK_INPUTS: [(&str, &[&str]); 4] = [
+    (
+        INPUT_CHECK_DEPENDABOT,
+        &[
+            "dependabot_enabled",
+            "dependabot_config",
+            "dependabot_coverage",
+            "dependabot_security_updates",
+            "dependabot_policy",
+            "dependabot_alerts",
+        ],
+    ),
+    (INPUT_CHECK_YELLR, &["yellr"]),
+    (INPUT_CHECK_BUNDLER_AUDIT, &["bundler_audit"]),
+    (
+        INPUT_CHECK_DEFAULT_BRANCH_PROTECTED,
+        &["default_branch_protected", "required_status_checks"],
+    ),
+];
+
+fn split_check_ids(value: &str) -> Vec<String> {
+    value
+        .split([',', '\n'])
+        .map(|c| c.trim())
+        .filter(|c| !c.is_empty())
+        .map(|c| c.to_owned())
+        .collect()
+}
 
 pub(crate) fn gather_inputs() -> Result<Inputs, Vec<String>> {
     let mut input_reader = InputReader::new();
@@ -155,12 +255,35 @@ pub(crate) fn gather_inputs() -> Result<Inputs, Vec<String>> {
     input_reader.read_bool_input(INPUT_CHECK_YELLR);
     input_reader.read_bool_input(INPUT_CHECK_BUNDLER_AUDIT);
     input_reader.read_bool_input(INPUT_CHECK_DEFAULT_BRANCH_PROTECTED);
+    input_reader.read_optional_str_input(INPUT_ENABLE_CHECKS, "");
+    input_reader.read_optional_str_input(INPUT_DISABLE_CHECKS, "");
+    input_reader.read_optional_str_input(INPUT_FAIL_ON, "error");
+    input_reader.read_optional_str_input(INPUT_SARIF_PATH, "");
+    input_reader.read_optional_str_input(INPUT_JSON_REPORT_PATH, "");
+    input_reader.read_optional_str_input(INPUT_JUNIT_PATH, "");
+    input_reader.read_optional_bool_input(INPUT_CREATE_CHECK_RUN, false);
+    input_reader.read_optional_bool_input(INPUT_COMMENT_ON_PULL_REQUEST, false);
 
-    if input_reader.failures.len() > 0 {
+    if !input_reader.failures.is_empty() {
         return Err(input_reader.failures.clone());
     }
 
     unsafe {
+        let fail_on = match Severity::parse(input_reader.get_str_input(INPUT_FAIL_ON)) {
+            Some(Severity::Notice) | None => {
+                return Err(vec![format!(
+                    "{} must be either `error` or `warning`.",
+                    INPUT_FAIL_ON
+                )])
+            }
+            Some(s) => s,
+        };
+        let mut disable_checks = split_check_ids(input_reader.get_str_input(INPUT_DISABLE_CHECKS));
+        for (input, check_ids) in LEGACY_CHECK_INPUTS {
+            if !input_reader.get_bool_input(input) {
+                disable_checks.extend(check_ids.iter().map(|c| (*c).to_owned()));
+            }
+        }
         Ok(Inputs {
             repository_owner: input_reader.get_str_env(GITHUB_REPOSITORY_OWNER).to_owned(),
             repository: input_reader.get_str_env(GITHUB_REPOSITORY).to_owned(),
@@ -168,11 +291,18 @@ pub(crate) fn gather_inputs() -> Result<Inputs, Vec<String>> {
             sha: input_reader.get_str_env(GITHUB_SHA).to_owned(),
             access_token: input_reader.get_str_input(INPUT_ACCESS_TOKEN).to_owned(),
             step_summary_path: input_reader.get_str_env(GITHUB_STEP_SUMMARY).to_owned(),
-            check_dependabot: input_reader.get_bool_input(INPUT_CHECK_DEPENDABOT),
-            check_yellr: input_reader.get_bool_input(INPUT_CHECK_YELLR),
-            check_bundler_audit: input_reader.get_bool_input(INPUT_CHECK_BUNDLER_AUDIT),
-            check_default_branch_protected: input_reader
-                .get_bool_input(INPUT_CHECK_DEFAULT_BRANCH_PROTECTED),
+            enable_checks: split_check_ids(input_reader.get_str_input(INPUT_ENABLE_CHECKS)),
+            disable_checks,
+            fail_on,
+            sarif_path: input_reader.get_str_input(INPUT_SARIF_PATH).to_owned(),
+            json_report_path: input_reader
+                .get_str_input(INPUT_JSON_REPORT_PATH)
+                .to_owned(),
+            junit_path: input_reader.get_str_input(INPUT_JUNIT_PATH).to_owned(),
+            create_check_run: input_reader.get_bool_input(INPUT_CREATE_CHECK_RUN),
+            comment_on_pull_request: input_reader.get_bool_input(INPUT_COMMENT_ON_PULL_REQUEST),
+            pull_request: read_pull_request_event(),
+            config: RepoConfig::default(),
         })
     }
 }
@@ -1,27 +1,43 @@
-use std::{error::Error, fs::OpenOptions, io::Write, process::exit};
+use std::{error::Error, fs::OpenOptions, io::Write, process::exit, time::Duration};
+
+mod action_utils;
 
 mod github_utils;
 
+mod glob_utils;
+
 mod checks;
 
+mod config;
+
 mod inputs;
 
+mod reports;
+
 mod results;
 
+mod waivers;
+
+mod workflow_utils;
+
+mod yaml_utils;
+
 use github_actions::issue_command;
 
-use tokio::{runtime::Builder, task::JoinSet};
+use tokio::runtime::Builder;
 
 use crate::{
-    checks::{
-        branch_protection::verify_default_branch_protected, copilot::verify_copilot_yaml,
-        dependabot::*, quality::*, rails_projects::verify_rails_projects,
-    },
+    checks::{registry, CheckCategory},
     github_utils::RateThrottle,
-    results::CheckResult,
+    results::{CheckReport, CheckResult, Location, Severity},
 };
 
 fn main() -> Result<(), Box<dyn Error>> {
+    if std::env::args().any(|a| a == "--list-checks") {
+        print!("{}", registry::list_checks());
+        return Ok(());
+    }
+
     let inputs = inputs::gather_inputs();
 
     if inputs.is_err() {
@@ -31,62 +47,133 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let mut failed = false;
 
-    let input_result = inputs.unwrap().clone();
+    let mut input_result = inputs.unwrap().clone();
 
     let rt = Builder::new_multi_thread().enable_all().build().unwrap();
 
     let requests = RateThrottle::new();
 
-    let results = rt.block_on(async {
-        let mut set = JoinSet::new();
-
-        if input_result.check_dependabot {
-            set.spawn(verify_dependabot(requests.clone(), input_result.clone()));
-        }
-        if input_result.check_yellr {
-            set.spawn(verify_updates_yellr(requests.clone(), input_result.clone()));
-        }
-        set.spawn(verify_copilot_yaml(requests.clone(), input_result.clone()));
-        set.spawn(verify_rails_projects(
-            requests.clone(),
-            input_result.clone(),
-        ));
-        if input_result.check_default_branch_protected {
-            set.spawn(verify_default_branch_protected(
-                requests.clone(),
-                input_result.clone(),
-            ));
+    let reports = rt.block_on(async {
+        let mut reports = Vec::new();
+        match config::load_config(requests.clone(), &input_result).await {
+            Ok(c) => input_result.config = c,
+            Err(e) => reports.push(CheckReport {
+                id: "config",
+                title: "Repository Configuration",
+                category: CheckCategory::Configuration,
+                location: Some(config::CONFIG_FILE_PATH),
+                remediation: "Fix or remove the repository configuration file.",
+                duration: Duration::ZERO,
+                results: vec![CheckResult::Failure(e)],
+            }),
         }
 
-        set.join_all()
-            .await
-            .iter()
-            .flat_map(|i| i)
-            .map(|i| (*i).to_owned())
-            .collect::<Vec<CheckResult>>()
+        reports.extend(registry::run_checks(requests.clone(), &input_result).await);
+        reports
     });
 
     let mut file = OpenOptions::new()
         .append(true)
         .create(true)
-        .open(input_result.step_summary_path)
+        .open(&input_result.step_summary_path)
         .unwrap();
 
-    for result in results {
-        match result.into_markdown() {
-            None => (),
-            Some(s) => {
+    for report in reports.iter() {
+        action_utils::group(report.title);
+        let default_location = report.location.map(Location::file);
+        for result in report.results.iter() {
+            if let Some(s) = result.to_markdown() {
                 file.write_all(s.as_bytes()).unwrap();
+                print!("{}", s);
             }
-        }
-        match result {
-            results::CheckResult::Failure(_) => {
+            let command = match result.severity() {
+                Some(Severity::Error) => "error",
+                Some(Severity::Warning) => "warning",
+                Some(Severity::Notice) => "notice",
+                None => continue,
+            };
+            action_utils::annotate(
+                command,
+                report.title,
+                result.location().or(default_location.as_ref()),
+                result.message().unwrap_or_default(),
+            );
+            if result.severity().is_some_and(|s| s >= input_result.fail_on) {
                 failed = true;
             }
-            _ => (),
+        }
+        action_utils::end_group();
+    }
+
+    if !input_result.sarif_path.is_empty() {
+        if let Err(e) = reports::sarif::write_sarif(&input_result.sarif_path, &reports) {
+            github_actions::error!(format!(
+                "Could not write SARIF report to {}: {}",
+                input_result.sarif_path, e
+            ));
+        }
+    }
+
+    if !input_result.json_report_path.is_empty() {
+        match reports::json::write_json(
+            &input_result.json_report_path,
+            &reports,
+            input_result.fail_on,
+        ) {
+            Ok(_) => {
+                let _ = github_actions::set_output("report_path", &input_result.json_report_path);
+            }
+            Err(e) => github_actions::error!(format!(
+                "Could not write JSON report to {}: {}",
+                input_result.json_report_path, e
+            )),
+        }
+    }
+
+    if !input_result.junit_path.is_empty() {
+        if let Err(e) =
+            reports::junit::write_junit(&input_result.junit_path, &reports, input_result.fail_on)
+        {
+            github_actions::error!(format!(
+                "Could not write JUnit report to {}: {}",
+                input_result.junit_path, e
+            ));
         }
     }
 
+    if input_result.create_check_run {
+        if let Err(e) = rt.block_on(reports::check_run::publish_check_run(
+            requests.clone(),
+            &input_result,
+            &reports,
+        )) {
+            github_actions::error!(e);
+        }
+    }
+
+    if input_result.comment_on_pull_request && input_result.pull_request.is_some() {
+        if let Err(e) = rt.block_on(reports::pr_comment::publish_pr_comment(
+            requests.clone(),
+            &input_result,
+            &reports,
+        )) {
+            github_actions::error!(e);
+        }
+    }
+
+    let failed_checks: Vec<&str> = reports
+        .iter()
+        .filter(|r| r.failed(input_result.fail_on))
+        .map(|r| r.id)
+        .collect();
+    let passed_count = reports
+        .iter()
+        .filter(|r| r.status(input_result.fail_on) == "passed")
+        .count();
+    let _ = github_actions::set_output("failed_checks", failed_checks.join(","));
+    let _ = github_actions::set_output("failed_count", failed_checks.len().to_string());
+    let _ = github_actions::set_output("passed_count", passed_count.to_string());
+
     if failed {
         file.write_all("\n**RESULT: FAILURE**\n".as_bytes())
             .unwrap();
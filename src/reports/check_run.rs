@@ -0,0 +1,124 @@
+use octocrab::params::checks::{
+    CheckRunConclusion, CheckRunOutput, CheckRunOutputAnnotation, CheckRunOutputAnnotationLevel,
+    CheckRunStatus,
+};
+
+use crate::{
+    github_utils::{octocrab_with_token_for, repo_name_for, RateThrottle},
+    inputs::Inputs,
+    reports::markdown::{to_markdown_table, truncate_markdown},
+    results::{CheckReport, Location, Severity},
+};
+
+pub(crate) static CHECK_RUN_NAME: &str = "IC Repo Compliance";
+
+// The checks API accepts at most 50 annotations per request.
+static MAX_ANNOTATIONS_PER_REQUEST: usize = 50;
+
+// The checks API rejects an output summary longer than this.
+static MAX_SUMMARY_LENGTH: usize = 65535;
+
+fn annotations_for(reports: &[CheckReport]) -> Vec<CheckRunOutputAnnotation> {
+    let mut annotations = Vec::new();
+    for report in reports {
+        let default_location = report.location.map(Location::file);
+        for result in report.results.iter() {
+            let level = match result.severity() {
+                Some(Severity::Error) => CheckRunOutputAnnotationLevel::Failure,
+                Some(Severity::Warning) => CheckRunOutputAnnotationLevel::Warning,
+                Some(Severity::Notice) => CheckRunOutputAnnotationLevel::Notice,
+                None => continue,
+            };
+            let location = match result.location().or(default_location.as_ref()) {
+                Some(l) => l,
+                None => continue,
+            };
+            let line = location.line.unwrap_or(1) as u32;
+            annotations.push(CheckRunOutputAnnotation {
+                path: location.path.clone(),
+                start_line: line,
+                end_line: line,
+                start_column: None,
+                end_column: None,
+                annotation_level: level,
+                message: result.message().unwrap_or_default().to_owned(),
+                title: Some(report.title.to_owned()),
+                raw_details: None,
+            });
+        }
+    }
+    annotations
+}
+
+fn output_for(
+    title: &str,
+    summary: &str,
+    annotations: Vec<CheckRunOutputAnnotation>,
+) -> CheckRunOutput {
+    CheckRunOutput {
+        title: title.to_owned(),
+        summary: summary.to_owned(),
+        text: None,
+        annotations,
+        images: Vec::new(),
+    }
+}
+
+/// Publishes the results as a completed check run on the commit being checked.
+pub(crate) async fn publish_check_run(
+    mut requests: RateThrottle,
+    inputs: &Inputs,
+    reports: &[CheckReport],
+) -> Result<(), String> {
+    let oc = octocrab_with_token_for(inputs);
+    let checks = oc.checks(inputs.repository_owner.clone(), repo_name_for(inputs));
+    let failed_count = reports.iter().filter(|r| r.failed(inputs.fail_on)).count();
+    let (conclusion, title) = if failed_count > 0 {
+        (
+            CheckRunConclusion::Failure,
+            format!("{} of {} checks failed", failed_count, reports.len()),
+        )
+    } else {
+        (
+            CheckRunConclusion::Success,
+            format!("All {} checks passed", reports.len()),
+        )
+    };
+    let summary = truncate_markdown(
+        &to_markdown_table(reports, inputs.fail_on),
+        MAX_SUMMARY_LENGTH,
+    );
+    let mut annotations = annotations_for(reports);
+    let remaining = annotations.split_off(annotations.len().min(MAX_ANNOTATIONS_PER_REQUEST));
+
+    let head_sha = match &inputs.pull_request {
+        Some(pr) => pr.head_sha.clone(),
+        None => inputs.sha.clone(),
+    };
+    let sem = requests.acquire().await;
+    let check_run = checks
+        .create_check_run(CHECK_RUN_NAME, head_sha)
+        .status(CheckRunStatus::Completed)
+        .conclusion(conclusion)
+        .output(output_for(&title, &summary, annotations))
+        .send()
+        .await
+        .map_err(|e| format!("Could not create the {} check run: {}", CHECK_RUN_NAME, e))?;
+    drop(sem);
+
+    let mut remaining = remaining.into_iter().peekable();
+    while remaining.peek().is_some() {
+        let batch: Vec<CheckRunOutputAnnotation> = remaining
+            .by_ref()
+            .take(MAX_ANNOTATIONS_PER_REQUEST)
+            .collect();
+        let _permit = requests.acquire().await;
+        checks
+            .update_check_run(check_run.id)
+            .output(output_for(&title, &summary, batch))
+            .send()
+            .await
+            .map_err(|e| format!("Could not annotate the {} check run: {}", CHECK_RUN_NAME, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::{fs::File, io::BufWriter};
+
+use serde_json::{json, Value};
+
+use crate::results::{CheckReport, CheckResult, Severity};
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Notice => "notice",
+    }
+}
+
+fn json_result(result: &CheckResult) -> Value {
+    json!({
+        "status": result.status(),
+        "severity": result.severity().map(severity_name),
+        "message": result.message(),
+        "location": result.location().map(|l| json!({ "path": l.path, "line": l.line })),
+    })
+}
+
+/// Builds the machine readable report, with one entry per check that ran.
+pub(crate) fn to_json(reports: &[CheckReport], fail_on: Severity) -> Value {
+    let checks: Vec<Value> = reports
+        .iter()
+        .map(|r| {
+            json!({
+                "id": r.id,
+                "title": r.title,
+                "category": r.category.to_string(),
+                "status": r.status(fail_on),
+                "remediation": r.remediation,
+                "duration_ms": r.duration.as_millis() as u64,
+                "results": r.results.iter().map(json_result).collect::<Vec<Value>>(),
+            })
+        })
+        .collect();
+    let failed = reports.iter().any(|r| r.failed(fail_on));
+    json!({
+        "result": if failed { "failure" } else { "success" },
+        "fail_on": severity_name(fail_on),
+        "checks": checks,
+    })
+}
+
+pub(crate) fn write_json(
+    path: &str,
+    reports: &[CheckReport],
+    fail_on: Severity,
+) -> std::io::Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &to_json(reports, fail_on))?;
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::{collections::BTreeMap, fs::File, io::Write};
+
+use crate::results::{CheckReport, CheckResult, Severity};
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn junit_testcase(report: &CheckReport, fail_on: Severity) -> String {
+    let mut testcase = format!(
+        "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n",
+        escape_xml(report.id),
+        escape_xml(&report.category.to_string()),
+        report.duration.as_secs_f64()
+    );
+    let messages: Vec<String> = report
+        .results
+        .iter()
+        .filter_map(|r| match r.location() {
+            Some(l) => r.message().map(|m| format!("{} ({})", m, l)),
+            None => r.message().map(|m| m.to_owned()),
+        })
+        .collect();
+    if report.failed(fail_on) {
+        let failures: Vec<String> = report
+            .results
+            .iter()
+            .filter(|r| r.severity().is_some_and(|s| s >= fail_on))
+            .filter_map(|r| r.message().map(|m| m.to_owned()))
+            .collect();
+        testcase.push_str(&format!(
+            "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+            escape_xml(&failures.join("; ")),
+            escape_xml(report.title),
+            escape_xml(&messages.join("\n"))
+        ));
+    } else if report
+        .results
+        .iter()
+        .all(|r| matches!(r.inner(), CheckResult::Ignore))
+    {
+        testcase.push_str("      <skipped/>\n");
+    } else {
+        testcase.push_str(&format!(
+            "      <system-out>{}</system-out>\n",
+            escape_xml(&messages.join("\n"))
+        ));
+    }
+    testcase.push_str("    </testcase>\n");
+    testcase
+}
+
+/// Renders a JUnit XML document with a test case per check, grouped into suites by category.
+pub(crate) fn to_junit(reports: &[CheckReport], fail_on: Severity) -> String {
+    let mut suites: BTreeMap<String, Vec<&CheckReport>> = BTreeMap::new();
+    for report in reports {
+        suites
+            .entry(report.category.to_string())
+            .or_default()
+            .push(report);
+    }
+    let count_failures = |rs: &[&CheckReport]| rs.iter().filter(|r| r.failed(fail_on)).count();
+    let count_skipped =
+        |rs: &[&CheckReport]| rs.iter().filter(|r| r.status(fail_on) == "skipped").count();
+    let all: Vec<&CheckReport> = reports.iter().collect();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+        env!("CARGO_PKG_NAME"),
+        all.len(),
+        count_failures(&all),
+        count_skipped(&all)
+    ));
+    for (name, suite) in suites {
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(&name),
+            suite.len(),
+            count_failures(&suite),
+            count_skipped(&suite),
+            suite.iter().map(|r| r.duration.as_secs_f64()).sum::<f64>()
+        ));
+        for report in suite {
+            xml.push_str(&junit_testcase(report, fail_on));
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+pub(crate) fn write_junit(
+    path: &str,
+    reports: &[CheckReport],
+    fail_on: Severity,
+) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(to_junit(reports, fail_on).as_bytes())?;
+    file.flush()
+}
@@ -0,0 +1,49 @@
+use crate::results::{CheckReport, Severity};
+
+fn status_icon(status: &str) -> &'static str {
+    match status {
+        "failed" => "\u{274c}",
+        "waived" => "\u{2796}",
+        "skipped" => "\u{23ed}\u{fe0f}",
+        _ => "\u{2705}",
+    }
+}
+
+/// A markdown table with a row per check, followed by every individual result.
+pub(crate) fn to_markdown_table(reports: &[CheckReport], fail_on: Severity) -> String {
+    let mut md = String::from("| Check | Id | Status |\n|-----|-----|-----|\n");
+    for report in reports {
+        let status = report.status(fail_on);
+        md.push_str(&format!(
+            "| {} | `{}` | {} {} |\n",
+            report.title,
+            report.id,
+            status_icon(status),
+            status
+        ));
+    }
+    md.push('\n');
+    for result in reports.iter().flat_map(|r| r.results.iter()) {
+        if let Some(s) = result.to_markdown() {
+            md.push_str(&s);
+        }
+    }
+    md
+}
+
+static TRUNCATED_NOTE: &str =
+    "\n_Some results were left out to fit; see the workflow run's step summary for all of them._\n";
+
+/// Cuts markdown down to at most `limit` bytes, at the end of a line, noting what was left out.
+pub(crate) fn truncate_markdown(md: &str, limit: usize) -> String {
+    if md.len() <= limit {
+        return md.to_owned();
+    }
+    let budget = limit.saturating_sub(TRUNCATED_NOTE.len());
+    let mut end = budget;
+    while !md.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = md[..end].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    format!("{}{}", &md[..end], TRUNCATED_NOTE)
+}
@@ -0,0 +1,11 @@
+pub(crate) mod check_run;
+
+pub(crate) mod json;
+
+pub(crate) mod junit;
+
+pub(crate) mod markdown;
+
+pub(crate) mod pr_comment;
+
+pub(crate) mod sarif;
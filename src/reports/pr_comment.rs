@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    checks::registry::{registered_checks, run_checks_matching},
+    config::load_config,
+    github_utils::{
+        octocrab_repo_handler_for, octocrab_with_token_for, repo_name_for, RateThrottle,
+    },
+    inputs::Inputs,
+    reports::markdown::{to_markdown_table, truncate_markdown},
+    results::{CheckReport, Severity},
+};
+
+// Hidden marker used to find the comment left by a previous run.
+static COMMENT_MARKER: &str = "<!-- ic-repo-checks -->";
+
+// Issue comments longer than this are rejected.
+static MAX_COMMENT_LENGTH: usize = 65536;
+
+// Who comments when the workflow's own token is used, which can't look itself up.
+static ACTIONS_BOT_LOGIN: &str = "github-actions[bot]";
+
+fn status_changes(
+    reports: &[CheckReport],
+    baseline: &[CheckReport],
+    default_branch: &str,
+    fail_on: Severity,
+) -> String {
+    let baseline_statuses: HashMap<&str, &str> =
+        baseline.iter().map(|r| (r.id, r.status(fail_on))).collect();
+    // Checks of repository settings give the same results for either commit, so aren't re-run.
+    let settings_checks: HashSet<&str> = registered_checks()
+        .iter()
+        .filter(|c| !c.reads_commit())
+        .map(|c| c.id())
+        .collect();
+    let mut changes = String::new();
+    for report in reports.iter().filter(|r| !settings_checks.contains(r.id)) {
+        let status = report.status(fail_on);
+        let previous = baseline_statuses
+            .get(report.id)
+            .copied()
+            .unwrap_or("not run");
+        if previous != status {
+            changes.push_str(&format!(
+                "| {} | `{}` | {} | {} |\n",
+                report.title, report.id, previous, status
+            ));
+        }
+    }
+    if changes.is_empty() {
+        format!(
+            "No checks changed status compared to `{}`.\n",
+            default_branch
+        )
+    } else {
+        format!(
+            "| Check | Id | `{}` | This Pull Request |\n|-----|-----|-----|-----|\n{}",
+            default_branch, changes
+        )
+    }
+}
+
+fn comment_body(
+    reports: &[CheckReport],
+    baseline: &[CheckReport],
+    default_branch: &str,
+    fail_on: Severity,
+) -> String {
+    let header = format!("{}\n## IC Repo Compliance\n\n", COMMENT_MARKER);
+    let changes = format!(
+        "\n### Changes from `{}`\n\n{}",
+        default_branch,
+        status_changes(reports, baseline, default_branch, fail_on)
+    );
+    // The results give way first, so the changes always fit.
+    let results = truncate_markdown(
+        &to_markdown_table(reports, fail_on),
+        MAX_COMMENT_LENGTH.saturating_sub(header.len() + changes.len()),
+    );
+    format!("{}{}{}", header, results, changes)
+}
+
+/// Creates, or updates, a single comment on the triggering pull request with the results.
+pub(crate) async fn publish_pr_comment(
+    mut requests: RateThrottle,
+    inputs: &Inputs,
+    reports: &[CheckReport],
+) -> Result<(), String> {
+    let pr = match &inputs.pull_request {
+        Some(pr) => pr,
+        None => return Ok(()),
+    };
+    let oc = octocrab_with_token_for(inputs);
+    let rh = octocrab_repo_handler_for(&oc, inputs);
+    let sem = requests.acquire().await;
+    let default_branch = rh
+        .get()
+        .await
+        .map_err(|_| "Could not comment on the pull request: Request failure.".to_owned())?
+        .default_branch
+        .unwrap_or_else(|| "main".to_owned());
+    drop(sem);
+
+    // The default branch is judged by its own configuration, falling back to the defaults if
+    // that can't be read.
+    let mut baseline_inputs = inputs.clone();
+    baseline_inputs.sha = default_branch.clone();
+    baseline_inputs.config = load_config(requests.clone(), &baseline_inputs)
+        .await
+        .unwrap_or_default();
+    let baseline =
+        run_checks_matching(requests.clone(), &baseline_inputs, |c| c.reads_commit()).await;
+    let body = comment_body(reports, &baseline, &default_branch, inputs.fail_on);
+
+    let issues = oc.issues(inputs.repository_owner.clone(), repo_name_for(inputs));
+    let sem = requests.acquire().await;
+    let login = oc
+        .current()
+        .user()
+        .await
+        .map(|u| u.login)
+        .unwrap_or_else(|_| ACTIONS_BOT_LOGIN.to_owned());
+    // Only our own comment is updated, even if someone else has pasted the marker into theirs.
+    let existing = match issues.list_comments(pr.number).per_page(100).send().await {
+        Ok(page) => oc.all_pages(page).await,
+        Err(e) => Err(e),
+    }
+    .map_err(|_| "Could not list pull request comments: Request failure.".to_owned())?
+    .into_iter()
+    .find(|c| {
+        c.user.login == login
+            && c.body
+                .as_deref()
+                .is_some_and(|b| b.contains(COMMENT_MARKER))
+    });
+    drop(sem);
+
+    let _permit = requests.acquire().await;
+    match existing {
+        Some(c) => issues.update_comment(c.id, body).await,
+        None => issues.create_comment(pr.number, body).await,
+    }
+    .map(|_| ())
+    .map_err(|_| "Could not comment on the pull request: Request failure.".to_owned())
+}
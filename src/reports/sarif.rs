@@ -0,0 +1,101 @@
+use std::{fs::File, io::BufWriter};
+
+use serde_json::{json, Value};
+
+use crate::results::{CheckReport, CheckResult, Severity};
+
+static SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+static TOOL_URI: &str = "https://github.com/TreyE/ic-repo-checks";
+
+// Code scanning rejects results without a location, so findings about the repository as a whole
+// are placed on the `.github` directory, which any repository running this action has.
+static REPOSITORY_LOCATION: &str = ".github";
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Notice => "note",
+    }
+}
+
+fn sarif_location(report: &CheckReport, result: &CheckResult) -> Value {
+    let (path, line) = match result.location() {
+        Some(l) => (l.path.as_str(), l.line),
+        None => (report.location.unwrap_or(REPOSITORY_LOCATION), None),
+    };
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": path },
+            "region": { "startLine": line.unwrap_or(1) },
+        },
+    })
+}
+
+fn sarif_result(rule_index: usize, report: &CheckReport, result: &CheckResult) -> Option<Value> {
+    let message = result.message()?;
+    let (level, suppressions) = match result.inner() {
+        CheckResult::Waived(_, severity, w) => (
+            sarif_level(*severity),
+            json!([{
+                "kind": "external",
+                "justification": format!("{} (expires {})", w.reason, w.expires),
+            }]),
+        ),
+        _ => (sarif_level(result.severity()?), json!([])),
+    };
+    Some(json!({
+        "ruleId": report.id,
+        "ruleIndex": rule_index,
+        "level": level,
+        "message": { "text": message },
+        "locations": [sarif_location(report, result)],
+        "suppressions": suppressions,
+    }))
+}
+
+/// Builds a SARIF 2.1.0 log with a rule per check and a result per finding.
+pub(crate) fn to_sarif(reports: &[CheckReport]) -> Value {
+    let rules: Vec<Value> = reports
+        .iter()
+        .map(|r| {
+            json!({
+                "id": r.id,
+                "name": r.title,
+                "shortDescription": { "text": r.title },
+                "properties": { "tags": [r.category.to_string()] },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = reports
+        .iter()
+        .enumerate()
+        .flat_map(|(i, report)| {
+            report
+                .results
+                .iter()
+                .filter_map(move |result| sarif_result(i, report, result))
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": TOOL_URI,
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+pub(crate) fn write_sarif(path: &str, reports: &[CheckReport]) -> std::io::Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &to_sarif(reports))?;
+    Ok(())
+}
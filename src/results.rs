@@ -1,16 +1,196 @@
+use std::{fmt::Display, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{checks::CheckCategory, config::Waiver};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    Notice,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub(crate) fn parse(value: &str) -> Option<Severity> {
+        match value.trim().to_lowercase().as_str() {
+            "notice" => Some(Severity::Notice),
+            "warning" => Some(Severity::Warning),
+            "error" => Some(Severity::Error),
+            _ => None,
+        }
+    }
+}
+
+/// A file, and optionally a line within it, which a result refers to.
+#[derive(Clone)]
+pub(crate) struct Location {
+    pub(crate) path: String,
+    pub(crate) line: Option<usize>,
+}
+
+impl Location {
+    pub(crate) fn file<T: Into<String>>(path: T) -> Self {
+        Location {
+            path: path.into(),
+            line: None,
+        }
+    }
+
+    pub(crate) fn line<T: Into<String>>(path: T, line: usize) -> Self {
+        Location {
+            path: path.into(),
+            line: Some(line),
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(l) => write!(f, "{}:{}", self.path, l),
+            None => f.write_str(&self.path),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) enum CheckResult {
     Pass(String),
     Failure(String),
+    Warning(String),
+    Notice(String),
+    /// A finding covered by a waiver, along with the severity it had before being waived.
+    Waived(String, Severity, Waiver),
+    Located(Location, Box<CheckResult>),
+    /// Extra markdown for the step summary, such as a table backing the findings.
+    Details(String),
     Ignore,
 }
 
 impl CheckResult {
-    pub(crate) fn into_markdown(&self) -> Option<String> {
+    pub(crate) fn at(self, location: Location) -> CheckResult {
+        CheckResult::Located(location, Box::new(self))
+    }
+
+    pub(crate) fn location(&self) -> Option<&Location> {
+        match self {
+            CheckResult::Located(l, _) => Some(l),
+            _ => None,
+        }
+    }
+
+    /// The result with any location stripped off.
+    pub(crate) fn inner(&self) -> &CheckResult {
+        match self {
+            CheckResult::Located(_, r) => r.inner(),
+            other => other,
+        }
+    }
+
+    pub(crate) fn status(&self) -> &'static str {
+        match self.inner() {
+            CheckResult::Pass(_) => "pass",
+            CheckResult::Failure(_) => "failure",
+            CheckResult::Warning(_) => "warning",
+            CheckResult::Notice(_) => "notice",
+            CheckResult::Waived(_, _, _) => "waived",
+            _ => "ignored",
+        }
+    }
+
+    pub(crate) fn message(&self) -> Option<&str> {
+        match self.inner() {
+            CheckResult::Pass(m)
+            | CheckResult::Failure(m)
+            | CheckResult::Warning(m)
+            | CheckResult::Notice(m)
+            | CheckResult::Waived(m, _, _) => Some(m.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The severity of a finding, or `None` for results which are not findings.
+    pub(crate) fn severity(&self) -> Option<Severity> {
+        match self.inner() {
+            CheckResult::Failure(_) => Some(Severity::Error),
+            CheckResult::Warning(_) => Some(Severity::Warning),
+            CheckResult::Notice(_) => Some(Severity::Notice),
+            _ => None,
+        }
+    }
+
+    /// Re-levels a finding to the given severity, leaving other results alone.
+    pub(crate) fn with_severity(self, severity: Severity) -> CheckResult {
+        match self {
+            CheckResult::Failure(m) | CheckResult::Warning(m) | CheckResult::Notice(m) => {
+                match severity {
+                    Severity::Error => CheckResult::Failure(m),
+                    Severity::Warning => CheckResult::Warning(m),
+                    Severity::Notice => CheckResult::Notice(m),
+                }
+            }
+            CheckResult::Located(l, r) => r.with_severity(severity).at(l),
+            other => other,
+        }
+    }
+
+    pub(crate) fn to_markdown(&self) -> Option<String> {
         match self {
             CheckResult::Pass(p) => Some(format!("\u{2705} {}\n", p)),
             CheckResult::Failure(f) => Some(format!("\u{274c} {}\n", f)),
+            CheckResult::Warning(w) => Some(format!("\u{26a0}\u{fe0f} {}\n", w)),
+            CheckResult::Notice(n) => Some(format!("\u{2139}\u{fe0f} {}\n", n)),
+            CheckResult::Waived(f, _, w) => Some(format!(
+                "\u{2796} Waived until {}: {} _({})_\n",
+                w.expires, f, w.reason
+            )),
+            CheckResult::Located(l, r) => r
+                .to_markdown()
+                .map(|m| format!("{} (`{}`)\n", m.trim_end(), l)),
+            CheckResult::Details(d) => Some(format!("\n{}\n", d.trim_end())),
             CheckResult::Ignore => None,
         }
     }
 }
+
+/// Every result produced by a single check, along with the details of the check itself.
+pub(crate) struct CheckReport {
+    pub(crate) id: &'static str,
+    pub(crate) title: &'static str,
+    pub(crate) category: CheckCategory,
+    pub(crate) location: Option<&'static str>,
+    pub(crate) remediation: &'static str,
+    pub(crate) duration: Duration,
+    pub(crate) results: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    pub(crate) fn failed(&self, fail_on: Severity) -> bool {
+        self.results
+            .iter()
+            .any(|r| r.severity().is_some_and(|s| s >= fail_on))
+    }
+
+    /// Overall status of the check: failed, waived, passed or skipped.
+    pub(crate) fn status(&self, fail_on: Severity) -> &'static str {
+        if self.failed(fail_on) {
+            "failed"
+        } else if self
+            .results
+            .iter()
+            .any(|r| matches!(r.inner(), CheckResult::Waived(_, _, _)))
+        {
+            "waived"
+        } else if self
+            .results
+            .iter()
+            .all(|r| matches!(r.inner(), CheckResult::Ignore))
+        {
+            "skipped"
+        } else {
+            "passed"
+        }
+    }
+}
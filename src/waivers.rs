@@ -0,0 +1,52 @@
+use chrono::NaiveDate;
+
+use crate::{
+    config::{RepoConfig, Waiver},
+    results::{CheckResult, Severity},
+};
+
+fn waiver_for<'a>(config: &'a RepoConfig, check_id: &str) -> Option<&'a Waiver> {
+    config
+        .waivers
+        .iter()
+        .filter(|w| w.check == check_id)
+        .max_by_key(|w| w.expires)
+}
+
+/// Marks failures and warnings of a waived check as waived, or notes the expiry of a lapsed waiver.
+pub(crate) fn apply_waivers(
+    config: &RepoConfig,
+    check_id: &str,
+    today: NaiveDate,
+    results: Vec<CheckResult>,
+) -> Vec<CheckResult> {
+    let waiver = match waiver_for(config, check_id) {
+        None => return results,
+        Some(w) => w,
+    };
+    results
+        .into_iter()
+        .map(|r| {
+            let (message, severity) = match (r.message(), r.severity()) {
+                (Some(m), Some(s)) if s >= Severity::Warning => (m.to_owned(), s),
+                _ => return r,
+            };
+            let waived = if waiver.reason.trim().is_empty() {
+                CheckResult::Failure(format!("{} (waiver ignored: no reason was given)", message))
+                    .with_severity(severity)
+            } else if waiver.expires < today {
+                CheckResult::Failure(format!(
+                    "{} (waiver expired on {})",
+                    message, waiver.expires
+                ))
+                .with_severity(severity)
+            } else {
+                CheckResult::Waived(message, severity, waiver.clone())
+            };
+            match r.location() {
+                Some(l) => waived.at(l.clone()),
+                None => waived,
+            }
+        })
+        .collect()
+}
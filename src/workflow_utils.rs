@@ -0,0 +1,105 @@
+use octocrab::Octocrab;
+use serde_yaml::Value;
+
+use crate::{
+    github_utils::{
+        grab_file, octocrab_repo_handler_for, request_failure_reason, GrabFileResult, RateThrottle,
+    },
+    inputs::Inputs,
+};
+
+static WORKFLOWS_DIR: &str = ".github/workflows/";
+
+pub(crate) fn is_workflow_path(path: &str) -> bool {
+    path.strip_prefix(WORKFLOWS_DIR)
+        .is_some_and(|p| !p.contains('/') && (p.ends_with(".yml") || p.ends_with(".yaml")))
+}
+
+/// Whether a step (or job) uses the given action, at any version.
+pub(crate) fn uses_action(step: &Value, action: &str) -> bool {
+    step.get("uses")
+        .and_then(|u| u.as_str())
+        .is_some_and(|u| u.split('@').next() == Some(action))
+}
+
+/// A GitHub Actions workflow file at `inputs.sha`.
+pub(crate) struct Workflow {
+    pub(crate) path: String,
+    pub(crate) text: String,
+    pub(crate) value: Value,
+}
+
+impl Workflow {
+    /// Each job id along with the job's definition.
+    pub(crate) fn jobs(&self) -> Vec<(&str, &Value)> {
+        match self.value.get("jobs").and_then(|j| j.as_mapping()) {
+            Some(jobs) => jobs
+                .iter()
+                .filter_map(|(k, v)| Some((k.as_str()?, v)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Fetches and parses every workflow in `.github/workflows`, given the paths in the repository.
+///
+/// Files which aren't valid YAML are skipped, since GitHub already reports those on every push.
+pub(crate) async fn grab_workflows(
+    requests: &mut RateThrottle,
+    oc: &Octocrab,
+    inputs: &Inputs,
+    paths: &[String],
+) -> Result<Vec<Workflow>, &'static str> {
+    let mut workflows = Vec::new();
+    for path in paths.iter().filter(|p| is_workflow_path(p)) {
+        let _permit = requests.acquire().await;
+        match grab_file(oc, inputs, path).await {
+            GrabFileResult::File(contents) => {
+                let text = String::from_utf8_lossy(&contents).into_owned();
+                if let Ok(value) = serde_yaml::from_str(&text) {
+                    workflows.push(Workflow {
+                        path: path.clone(),
+                        text,
+                        value,
+                    });
+                }
+            }
+            GrabFileResult::NotFound => {}
+            GrabFileResult::AccessDenied => return Err("Access denied."),
+            GrabFileResult::AccessForbidden => return Err("Access forbidden."),
+            GrabFileResult::Error(e) => return Err(request_failure_reason(&e)),
+        }
+    }
+    Ok(workflows)
+}
+
+/// Like [`grab_workflows`], but lists `.github/workflows` itself rather than needing every path in
+/// the repository, so it works however large the repository is.
+pub(crate) async fn grab_repo_workflows(
+    requests: &mut RateThrottle,
+    oc: &Octocrab,
+    inputs: &Inputs,
+) -> Result<Vec<Workflow>, &'static str> {
+    let sem = requests.acquire().await;
+    let listing = octocrab_repo_handler_for(oc, inputs)
+        .get_content()
+        .path(WORKFLOWS_DIR.trim_end_matches('/'))
+        .r#ref(inputs.sha.clone())
+        .send()
+        .await;
+    drop(sem);
+    let paths: Vec<String> = match listing {
+        Ok(contents) => contents
+            .items
+            .into_iter()
+            .filter(|c| c.r#type == "file")
+            .map(|c| c.path)
+            .collect(),
+        Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+            return Ok(Vec::new())
+        }
+        Err(e) => return Err(request_failure_reason(&e)),
+    };
+    grab_workflows(requests, oc, inputs, &paths).await
+}
@@ -0,0 +1,104 @@
+// serde_yaml values don't carry their positions, so these helpers find line numbers
+// by looking at indentation in the source text.  Line numbers are 1-based.
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+fn key_of(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start().trim_start_matches("- ");
+    let (key, _) = trimmed.split_once(':')?;
+    Some(key.trim().trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// Finds a key anywhere within the given range of lines.
+pub(crate) fn key_line_between(text: &str, key: &str, start: usize, end: usize) -> Option<usize> {
+    text.lines()
+        .enumerate()
+        .skip(start.saturating_sub(1))
+        .take(end + 1 - start.max(1))
+        .find(|(_, l)| is_content(l) && key_of(l) == Some(key))
+        .map(|(i, _)| i + 1)
+}
+
+pub(crate) fn top_level_key_line(text: &str, key: &str) -> Option<usize> {
+    text.lines()
+        .enumerate()
+        .find(|(_, l)| is_content(l) && indentation(l) == 0 && key_of(l) == Some(key))
+        .map(|(i, _)| i + 1)
+}
+
+/// Each key of the mapping under a top-level key, with the first and last line of its value.
+///
+/// Only keys at the indentation of the first one count, so a nested key of the same name is
+/// never mistaken for one of the mapping's own.
+pub(crate) fn mapping_key_lines<'a>(text: &'a str, key: &str) -> Vec<(&'a str, usize, usize)> {
+    let start = match top_level_key_line(text, key) {
+        Some(l) => l,
+        None => return Vec::new(),
+    };
+    let mut keys: Vec<(&str, usize, usize)> = Vec::new();
+    let mut key_indent = None;
+    for (i, line) in text.lines().enumerate().skip(start) {
+        if !is_content(line) {
+            continue;
+        }
+        let indent = indentation(line);
+        if indent == 0 {
+            break;
+        }
+        match key_indent {
+            None => key_indent = Some(indent),
+            Some(ki) if indent < ki => break,
+            _ => (),
+        }
+        match key_of(line) {
+            Some(k) if Some(indent) == key_indent => keys.push((k, i + 1, i + 1)),
+            _ => {
+                if let Some(last) = keys.last_mut() {
+                    last.2 = i + 1;
+                }
+            }
+        }
+    }
+    keys
+}
+
+/// The first and last line of each item in the sequence under a top-level key.
+pub(crate) fn sequence_item_lines(text: &str, key: &str) -> Vec<(usize, usize)> {
+    let start = match top_level_key_line(text, key) {
+        Some(l) => l,
+        None => return Vec::new(),
+    };
+    let mut items: Vec<(usize, usize)> = Vec::new();
+    let mut item_indent = None;
+    for (i, line) in text.lines().enumerate().skip(start) {
+        if !is_content(line) {
+            continue;
+        }
+        let indent = indentation(line);
+        if indent == 0 && !line.starts_with('-') {
+            break;
+        }
+        let is_item = line.trim_start().starts_with('-');
+        match item_indent {
+            None if is_item => {
+                item_indent = Some(indent);
+                items.push((i + 1, i + 1));
+            }
+            Some(ii) if is_item && indent == ii => items.push((i + 1, i + 1)),
+            Some(ii) if indent < ii => break,
+            _ => {
+                if let Some(last) = items.last_mut() {
+                    last.1 = i + 1;
+                }
+            }
+        }
+    }
+    items
+}